@@ -1,13 +1,18 @@
 use core::{alloc::GlobalAlloc};
 
+#[cfg(not(feature = "mock"))]
 #[global_allocator]
 static ALLOC: RedactedAllocator = RedactedAllocator;
 
+#[cfg(not(feature = "mock"))]
 unsafe extern "C" {
     pub unsafe fn malloc(size: usize) -> *mut u8;
     pub unsafe fn free(ptr: *mut u8, size: usize);
 }
 
+#[cfg(feature = "mock")]
+pub use crate::mock::alloc::{malloc, free};
+
 pub struct RedactedAllocator;
 
 unsafe impl GlobalAlloc for RedactedAllocator {
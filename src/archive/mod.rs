@@ -0,0 +1,178 @@
+//! Readers for ustar tar and zip archives, over anything that is `Read + Seek`
+//! like a [`File`](crate::fs::File) or an [`io::Cursor`].
+//!
+//! ```ignore
+//! let mut bundle = ZipArchive::new(File::open("/apps/game/assets.zip")?)?;
+//! for entry in bundle.entries() {
+//!     println!("{} ({} bytes)", entry.path().display(), entry.size());
+//! }
+//! let mut level = String::new();
+//! bundle.by_name("levels/1.txt")?.read_to_string(&mut level)?;
+//! ```
+
+use core::ops::Range;
+
+use alloc::boxed::Box;
+use path::{Path, PathBuf};
+use unix_str::UnixStr;
+
+use crate::{flate::{Crc32, Inflater}, fs::FileType, io::{self, Error, ErrorKind, Read, Seek, SeekFrom, Take}};
+
+mod tar;
+pub use tar::*;
+mod zip;
+pub use zip::*;
+
+/// A file or directory stored in an archive.
+#[derive(Debug, Clone)]
+pub struct Entry {
+    path: PathBuf,
+    size: u64,
+    ty: FileType,
+    source: Source,
+}
+
+// where the data is and how it's stored, depends on the format
+#[derive(Debug, Clone, Copy)]
+enum Source {
+    Tar { offset: u64 },
+    Zip { header: u64, compressed_size: u64, method: u16, crc: u32, encrypted: bool },
+}
+
+impl Entry {
+    /// The path inside the archive, relative and without a trailing `/` for directories.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Size of the data once extracted.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Size of the data as stored in the archive.
+    pub fn compressed_size(&self) -> u64 {
+        match self.source {
+            Source::Tar { .. } => self.size,
+            Source::Zip { compressed_size, .. } => compressed_size,
+        }
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.ty
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.ty.is_file()
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.ty.is_dir()
+    }
+
+    // where the data sits in the archive when it's stored as is
+    pub(crate) fn stored_range(&self) -> Option<Range<u64>> {
+        match self.source {
+            Source::Tar { offset } => Some(offset..offset + self.size),
+            Source::Zip { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Decoder<'a, R> {
+    Stored(Take<&'a mut R>),
+    Deflated(Box<Inflater<Take<&'a mut R>>>),
+}
+
+/// Reads the data of one [`Entry`], made by `by_index` or `by_name` on an archive.
+///
+/// Zip entries are checked against their CRC-32 by the read that returns their
+/// last byte, a mismatch is reported as [`ErrorKind::InvalidData`].
+#[derive(Debug)]
+pub struct EntryReader<'a, R> {
+    entry: &'a Entry,
+    decoder: Decoder<'a, R>,
+    crc: Option<(Crc32, u32)>,
+    read: u64,
+}
+
+impl<'a, R: Read + Seek> EntryReader<'a, R> {
+    fn open(inner: &'a mut R, entry: &'a Entry) -> io::Result<Self> {
+        if entry.is_dir() {
+            return Err(ErrorKind::IsADirectory.into());
+        }
+        let (decoder, crc) = match entry.source {
+            Source::Tar { offset } => {
+                inner.seek(SeekFrom::Start(offset))?;
+                (Decoder::Stored(inner.take(entry.size)), None)
+            },
+            Source::Zip { header, compressed_size, method, crc, encrypted } => {
+                if encrypted {
+                    return Err(Error::const_new(ErrorKind::Unsupported, "encrypted zip entries aren't supported"));
+                }
+                let offset = zip::data_offset(inner, header)?;
+                inner.seek(SeekFrom::Start(offset))?;
+                let data = inner.take(compressed_size);
+                let decoder = match method {
+                    zip::STORED => Decoder::Stored(data),
+                    zip::DEFLATED => Decoder::Deflated(Box::new(Inflater::new(data))),
+                    _ => return Err(Error::const_new(ErrorKind::Unsupported, "zip compression method isn't supported")),
+                };
+                (decoder, Some((Crc32::new(), crc)))
+            },
+        };
+        Ok(Self { entry, decoder, crc, read: 0 })
+    }
+}
+
+impl<R> EntryReader<'_, R> {
+    pub fn entry(&self) -> &Entry {
+        self.entry
+    }
+}
+
+impl<R: Read> Read for EntryReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = match &mut self.decoder {
+            Decoder::Stored(data) => data.read(buf)?,
+            Decoder::Deflated(data) => data.read(buf)?,
+        };
+        self.read += n as u64;
+        if let Some((crc, _)) = &mut self.crc {
+            crc.update(&buf[..n]);
+        }
+        let at_end = n == 0 && !buf.is_empty();
+        if self.read > self.entry.size || (at_end && self.read != self.entry.size) {
+            return Err(Error::const_new(ErrorKind::InvalidData, "archive entry has a different size than recorded"));
+        }
+        // checked as soon as the last byte is out, `read_exact` never asks for more
+        if (n != 0 || at_end) && self.read == self.entry.size
+            && let Some((crc, expected)) = self.crc
+            && crc.value() != expected
+        {
+            return Err(Error::const_new(ErrorKind::InvalidData, "archive entry fails its checksum"));
+        }
+        Ok(n)
+    }
+}
+
+// both formats store directories with a trailing `/` and tar may start names
+// with `./`, `Entry::path` leaves both off
+fn entry_path(path: PathBuf) -> PathBuf {
+    let mut bytes = path.as_unix_str().as_bytes();
+    while let Some(rest) = bytes.strip_prefix(b"./") {
+        let slashes = rest.iter().take_while(|&&b| b == b'/').count();
+        bytes = &rest[slashes..];
+    }
+    let len = bytes.iter().rposition(|&b| b != b'/').map_or(0, |last| last + 1);
+    let bytes = &bytes[..len];
+    PathBuf::from(UnixStr::from_bytes(if bytes == b"." { b"" } else { bytes }))
+}
+
+// finds an entry for `by_name`, compared by components so `a/./b` finds `a/b`
+fn find(entries: &[Entry], path: &Path) -> io::Result<usize> {
+    entries.iter()
+        .position(|entry| entry.path == path)
+        .ok_or(Error::const_new(ErrorKind::NotFound, "no such entry in the archive"))
+}
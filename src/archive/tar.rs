@@ -0,0 +1,165 @@
+use core::slice;
+
+use alloc::vec::Vec;
+use path::{Path, PathBuf};
+use unix_str::UnixStr;
+
+use crate::{archive::{entry_path, find, Entry, EntryReader, Source}, fs::FileType, io::{self, Error, ErrorKind, Read, Seek, SeekFrom}};
+
+const BLOCK: u64 = 512;
+
+const MALFORMED: Error = Error::const_new(ErrorKind::InvalidData, "malformed tar archive");
+
+// longest name a GNU or pax header may hand the next entry
+const MAX_LONG_NAME: u64 = 64 * 1024;
+
+/// A ustar archive, the headers are all read up front.
+///
+/// Regular files and directories are listed, other entries like links are
+/// skipped. Names longer than the header fits are taken from GNU `L` entries
+/// and the `path` of pax headers.
+#[derive(Debug)]
+pub struct TarArchive<R> {
+    inner: R,
+    entries: Vec<Entry>,
+}
+
+impl<R: Read + Seek> TarArchive<R> {
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let end = inner.seek(SeekFrom::End(0))?;
+        let mut entries = Vec::new();
+        // the full name of the next entry, from a GNU `L` entry or a pax header
+        let mut long_name: Option<Vec<u8>> = None;
+        let mut pos = 0;
+        // a missing end marker is tolerated as long as the last entry is whole
+        while pos < end {
+            if end - pos < BLOCK {
+                return Err(Error::const_new(ErrorKind::UnexpectedEof, "tar archive ends inside a header"));
+            }
+            let mut header = [0; BLOCK as usize];
+            inner.seek(SeekFrom::Start(pos))?;
+            inner.read_exact(&mut header)?;
+            // the archive ends with zeroed blocks
+            if header.iter().all(|&b| b == 0) {
+                break;
+            }
+            if &header[257..262] != b"ustar" || checksum(&header) != octal(&header[148..156])? {
+                return Err(MALFORMED);
+            }
+
+            let size = octal(&header[124..136])?;
+            let offset = pos + BLOCK;
+            if end - offset < size {
+                return Err(Error::const_new(ErrorKind::UnexpectedEof, "tar archive ends inside a file"));
+            }
+            let ty = match header[156] {
+                b'0' | b'7' | 0 => Some(FileType::FILE),
+                b'5' => Some(FileType::DIR),
+                b'L' => {
+                    long_name = Some(field(&read_data(&mut inner, size)?).to_vec());
+                    None
+                },
+                b'x' => {
+                    if let Some(path) = pax_path(&read_data(&mut inner, size)?)? {
+                        long_name = Some(path);
+                    }
+                    None
+                },
+                _ => None,
+            };
+            if let Some(ty) = ty {
+                let path = match long_name.take() {
+                    Some(name) => PathBuf::from(UnixStr::from_bytes(&name)),
+                    None => {
+                        let mut path = PathBuf::from(UnixStr::from_bytes(field(&header[345..500])));
+                        path.push(UnixStr::from_bytes(field(&header[..100])));
+                        path
+                    },
+                };
+                let path = entry_path(path);
+                // `tar -C dir .` starts with an entry for `./` itself
+                if !path.as_unix_str().is_empty() {
+                    entries.push(Entry { path, size, ty, source: Source::Tar { offset } });
+                }
+            }
+            pos = offset + size.div_ceil(BLOCK) * BLOCK;
+        }
+        Ok(Self { inner, entries })
+    }
+
+    pub fn entries(&self) -> slice::Iter<'_, Entry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Opens the `index`th entry, panics if there aren't that many.
+    pub fn by_index(&mut self, index: usize) -> io::Result<EntryReader<'_, R>> {
+        EntryReader::open(&mut self.inner, &self.entries[index])
+    }
+
+    pub fn by_name<P: AsRef<Path>>(&mut self, path: P) -> io::Result<EntryReader<'_, R>> {
+        let index = find(&self.entries, path.as_ref())?;
+        self.by_index(index)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+// the data of a GNU or pax header entry, the reader is right after its header
+fn read_data<R: Read>(inner: &mut R, size: u64) -> io::Result<Vec<u8>> {
+    if size > MAX_LONG_NAME {
+        return Err(Error::const_new(ErrorKind::Unsupported, "tar entry name too long"));
+    }
+    let mut data = alloc::vec![0; size as usize];
+    inner.read_exact(&mut data)?;
+    Ok(data)
+}
+
+// the `path` record of pax extended header data, records look like `<len> <key>=<value>\n`
+fn pax_path(mut data: &[u8]) -> io::Result<Option<Vec<u8>>> {
+    let mut path = None;
+    while !data.is_empty() {
+        let space = data.iter().position(|&b| b == b' ').ok_or(MALFORMED)?;
+        let len = core::str::from_utf8(&data[..space]).ok().and_then(|len| len.parse::<usize>().ok())
+            .filter(|&len| len > space && len <= data.len())
+            .ok_or(MALFORMED)?;
+        let record = data[space + 1..len].strip_suffix(b"\n").ok_or(MALFORMED)?;
+        if let Some(value) = record.strip_prefix(b"path=") {
+            path = Some(value.to_vec());
+        }
+        data = &data[len..];
+    }
+    Ok(path)
+}
+
+// a NUL-padded header field
+fn field(bytes: &[u8]) -> &[u8] {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    &bytes[..len]
+}
+
+// a number field, octal digits padded with spaces or NULs
+fn octal(bytes: &[u8]) -> io::Result<u64> {
+    let mut value: u64 = 0;
+    for &b in bytes.iter().skip_while(|&&b| b == b' ').take_while(|&&b| b != 0 && b != b' ') {
+        if !(b'0'..=b'7').contains(&b) {
+            return Err(MALFORMED);
+        }
+        value = value.checked_mul(8).ok_or(MALFORMED)? + (b - b'0') as u64;
+    }
+    Ok(value)
+}
+
+// the header bytes summed with the checksum field counted as spaces
+fn checksum(header: &[u8]) -> u64 {
+    header.iter().enumerate().map(|(i, &b)| if (148..156).contains(&i) { b' ' } else { b } as u64).sum()
+}
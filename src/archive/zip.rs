@@ -0,0 +1,130 @@
+use core::slice;
+
+use alloc::{vec, vec::Vec};
+use path::{Path, PathBuf};
+use unix_str::UnixStr;
+
+use crate::{archive::{entry_path, find, Entry, EntryReader, Source}, fs::FileType, io::{self, Error, ErrorKind, Read, Seek, SeekFrom}};
+
+pub(super) const STORED: u16 = 0;
+pub(super) const DEFLATED: u16 = 8;
+
+const END_SIGNATURE: u32 = 0x0605_4B50;
+const CENTRAL_SIGNATURE: u32 = 0x0201_4B50;
+const LOCAL_SIGNATURE: u32 = 0x0403_4B50;
+
+const END_SIZE: usize = 22;
+const CENTRAL_SIZE: usize = 46;
+const LOCAL_SIZE: usize = 30;
+
+const MALFORMED: Error = Error::const_new(ErrorKind::InvalidData, "malformed zip archive");
+
+/// A zip archive with stored or deflated entries, the central directory is
+/// read up front.
+///
+/// Zip64 and encrypted archives aren't supported.
+#[derive(Debug)]
+pub struct ZipArchive<R> {
+    inner: R,
+    entries: Vec<Entry>,
+}
+
+impl<R: Read + Seek> ZipArchive<R> {
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let end = inner.seek(SeekFrom::End(0))?;
+        // the end record sits at the very end, followed by a comment of up to 64K
+        let tail_len = end.min((END_SIZE + u16::MAX as usize) as u64);
+        let mut tail = vec![0; tail_len as usize];
+        inner.seek(SeekFrom::Start(end - tail_len))?;
+        inner.read_exact(&mut tail)?;
+        let record = (0..=tail.len().saturating_sub(END_SIZE)).rev()
+            .find(|&i| tail.len() >= END_SIZE && u32_at(&tail, i) == END_SIGNATURE)
+            .map(|i| &tail[i..])
+            .ok_or(Error::const_new(ErrorKind::InvalidData, "not a zip archive"))?;
+
+        let count = u16_at(record, 10);
+        let size = u32_at(record, 12);
+        let offset = u32_at(record, 16);
+        if count == u16::MAX || size == u32::MAX || offset == u32::MAX {
+            return Err(Error::const_new(ErrorKind::Unsupported, "zip64 archives aren't supported"));
+        }
+        if offset as u64 + size as u64 > end {
+            return Err(MALFORMED);
+        }
+        let mut directory = vec![0; size as usize];
+        inner.seek(SeekFrom::Start(offset as u64))?;
+        inner.read_exact(&mut directory)?;
+
+        let mut entries = Vec::with_capacity(count as usize);
+        let mut rest = &directory[..];
+        for _ in 0..count {
+            if rest.len() < CENTRAL_SIZE || u32_at(rest, 0) != CENTRAL_SIGNATURE {
+                return Err(MALFORMED);
+            }
+            let name_len = u16_at(rest, 28) as usize;
+            let record_len = CENTRAL_SIZE + name_len + u16_at(rest, 30) as usize + u16_at(rest, 32) as usize;
+            if rest.len() < record_len {
+                return Err(MALFORMED);
+            }
+            let name = &rest[CENTRAL_SIZE..CENTRAL_SIZE + name_len];
+            let source = Source::Zip {
+                header: u32_at(rest, 42) as u64,
+                compressed_size: u32_at(rest, 20) as u64,
+                method: u16_at(rest, 10),
+                crc: u32_at(rest, 16),
+                encrypted: u16_at(rest, 8) & 1 != 0,
+            };
+            // directories are only told apart by the trailing slash
+            let ty = if name.ends_with(b"/") { FileType::DIR } else { FileType::FILE };
+            let path = entry_path(PathBuf::from(UnixStr::from_bytes(name)));
+            entries.push(Entry { path, size: u32_at(rest, 24) as u64, ty, source });
+            rest = &rest[record_len..];
+        }
+        Ok(Self { inner, entries })
+    }
+
+    pub fn entries(&self) -> slice::Iter<'_, Entry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Opens the `index`th entry, panics if there aren't that many.
+    pub fn by_index(&mut self, index: usize) -> io::Result<EntryReader<'_, R>> {
+        EntryReader::open(&mut self.inner, &self.entries[index])
+    }
+
+    pub fn by_name<P: AsRef<Path>>(&mut self, path: P) -> io::Result<EntryReader<'_, R>> {
+        let index = find(&self.entries, path.as_ref())?;
+        self.by_index(index)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+// the data starts after the local header, whose name and extra field can differ from the central one
+pub(super) fn data_offset<R: Read + Seek>(inner: &mut R, header: u64) -> io::Result<u64> {
+    let mut local = [0; LOCAL_SIZE];
+    inner.seek(SeekFrom::Start(header))?;
+    inner.read_exact(&mut local)?;
+    if u32_at(&local, 0) != LOCAL_SIGNATURE {
+        return Err(MALFORMED);
+    }
+    Ok(header + (LOCAL_SIZE + u16_at(&local, 26) as usize + u16_at(&local, 28) as usize) as u64)
+}
+
+fn u16_at(buf: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([buf[at], buf[at + 1]])
+}
+
+fn u32_at(buf: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([buf[at], buf[at + 1], buf[at + 2], buf[at + 3]])
+}
@@ -20,7 +20,7 @@ pub struct TraceFrame {
 impl Backtrace {
     #[inline(never)]
     pub fn new() -> Self {
-        Self::create(Self::new as usize)
+        Self::create(Self::new as fn() -> Self as usize)
     }
 
     fn create(ip: usize) -> Backtrace {
@@ -43,13 +43,19 @@ impl Backtrace {
     }
 }
 
+impl Default for Backtrace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Display for Backtrace {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let frames = &self.frames[self.start..];
 
         writeln!(f, "stack backtrace:")?;
-        for i in 0..frames.len() {
-            writeln!(f, "  {}: {:#x}", i + 1, frames[i].frame.return_addr)?;
+        for (i, frame) in frames.iter().enumerate() {
+            writeln!(f, "  {}: {:#x}", i + 1, frame.frame.return_addr)?;
         }
 
         Ok(())
@@ -1,3 +1,4 @@
+#[cfg(target_arch = "aarch64")]
 use core::arch::asm;
 
 
@@ -19,6 +20,7 @@ pub struct FrameRecord {
 }
 
 impl FrameRecord {
+    #[cfg(target_arch = "aarch64")]
     pub fn current() -> *const Self {
         unsafe { 
             let x29: *const Self;
@@ -26,6 +28,12 @@ impl FrameRecord {
             x29
         }
     }
+
+    // frame pointers aren't guaranteed on the host so the mock doesn't walk the stack
+    #[cfg(not(target_arch = "aarch64"))]
+    pub fn current() -> *const Self {
+        core::ptr::null()
+    }
 }
 
 pub fn trace(cb: &mut dyn FnMut(&FrameRecord) -> bool, depth: usize) {
@@ -0,0 +1,336 @@
+//! Process arguments, environment variables and the current directory.
+//!
+//! # Arguments at start-up
+//!
+//! RedactedOS starts a process through its C `main(argc, argv)`, `argv` being
+//! `argc` pointers to NUL-terminated strings with the program first. Rust's
+//! `main` wrapper hands both to `rt::lang_start`, which passes them on to
+//! [`init_args`]. Start-up code of its own, like a `#![no_main]` entry point,
+//! calls [`init_args`] the same way.
+//!
+//! When the process got no arguments (`argc` is 0), they are read from
+//! [`ARGS_FILE`] instead the first time they're asked for, which lets a
+//! launcher pass arguments on a system that can't hand them over yet. The file
+//! is removed once read, and if another process removed it first the arguments
+//! were meant for that one, so they reach one process at most. A system that
+//! can't remove files (RedactedOS without the `proposed-abi` calls) leaves the
+//! file in place, and every process started without arguments after it reads
+//! the same ones until the launcher replaces it.
+//!
+//! # Environment at start-up
+//!
+//! The variables come from the `KEY=VALUE` strings RedactedOS hands a process
+//! started by [`Command`](crate::process::Command), which `rt::lang_start` gets
+//! through `get_environ` and passes on to [`init_env`]. That call is only linked
+//! with the `proposed-abi` feature, without it the environment starts out empty.
+//! `PWD` among them is where [`current_dir`] starts out. Either way the
+//! environment only lives in this process.
+//!
+//! No lock here is held while anything else runs, so the functions that can't
+//! return an error only panic on the locks being taken if the crate itself is
+//! broken.
+
+use core::{ffi::{c_char, CStr}, fmt};
+
+use alloc::{collections::{btree_map, BTreeMap}, string::String, vec::{self, Vec}};
+use path::{Path, PathBuf};
+use unix_str::{UnixStr, UnixString};
+
+use crate::{fs, io::{self, Error, ErrorKind}, sync::global};
+
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+unsafe extern "C" {
+    // the `envp` the process was spawned with, a null-terminated list of `KEY=VALUE`
+    // strings, or null if it got none
+    #[link_name = "get_environ"]
+    pub unsafe fn ffi_get_environ() -> *const *const c_char;
+}
+
+#[cfg(feature = "mock")]
+pub use crate::mock::process::ffi_get_environ;
+
+#[cfg(not(feature = "proposed-abi"))]
+pub use crate::proposed::ffi_get_environ;
+
+// RedactedOS has no working directory of its own, so it's kept here and `fs`
+// resolves relative paths against it before they reach the C side
+global! {
+    static CURRENT_DIR: Option<PathBuf> = None;
+}
+
+const BUSY: Error = Error::const_new(ErrorKind::ResourceBusy, "current directory is being changed");
+
+/// The directory relative paths are resolved against, `/` until it gets changed.
+pub fn current_dir() -> io::Result<PathBuf> {
+    let dir = CURRENT_DIR.try_lock().ok_or(BUSY)?;
+    Ok(dir.clone().unwrap_or_else(|| PathBuf::from("/")))
+}
+
+/// Changes the current directory, `path` may itself be relative to the old one.
+///
+/// Without `stat` there's no telling a file from a directory, so then any path
+/// [`fs::canonicalize`] accepts is taken.
+pub fn set_current_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = fs::canonicalize(path)?;
+    match fs::metadata(&path) {
+        Ok(meta) if !meta.is_dir() => return Err(ErrorKind::NotADirectory.into()),
+        Err(err) if err.kind() != ErrorKind::Unsupported => return Err(err),
+        _ => {},
+    }
+    *CURRENT_DIR.try_lock().ok_or(BUSY)? = Some(path);
+    Ok(())
+}
+
+/// Where the arguments come from when the process was started without any, one
+/// per line laid out like `argv`, the program first.
+///
+/// A launcher writes it right before starting the program, the first process
+/// without arguments that asks for them takes it if files can be removed.
+pub const ARGS_FILE: &str = "/.args";
+
+const BUSY_ARGS: &str = "arguments are being changed";
+const BUSY_VARS: &str = "environment is being changed";
+
+// `None` until the arguments are known, from argv or the fallback file
+global! {
+    static ARGS: Option<Vec<UnixString>> = None;
+}
+
+global! {
+    static VARS: BTreeMap<UnixString, UnixString> = BTreeMap::new();
+}
+
+/// Sets the arguments from a C `argv`, an `argc` of 0 leaves them to [`ARGS_FILE`].
+///
+/// # Safety
+///
+/// `argv` has to point to `argc` pointers to NUL-terminated strings, a null
+/// pointer among them ends the list early.
+pub unsafe fn init_args(argc: isize, argv: *const *const u8) {
+    let args = match argc > 0 && !argv.is_null() {
+        true => Some((0..argc as usize)
+            .map(|i| unsafe { *argv.add(i) })
+            .take_while(|arg| !arg.is_null())
+            .map(|arg| UnixString::from_vec(unsafe { CStr::from_ptr(arg as *const c_char) }.to_bytes().to_vec()))
+            .collect()),
+        false => None,
+    };
+    *ARGS.try_lock().expect(BUSY_ARGS) = args;
+}
+
+/// Sets the environment from a C `envp`, a null `envp` leaves it as it is.
+///
+/// Strings without a `=` are skipped. An absolute `PWD` becomes the current
+/// directory without checking it exists.
+///
+/// # Safety
+///
+/// `envp` has to be null or point to a list of pointers to NUL-terminated
+/// strings that ends with a null pointer.
+pub unsafe fn init_env(envp: *const *const u8) {
+    if envp.is_null() {
+        return;
+    }
+    let mut vars = BTreeMap::new();
+    for i in 0.. {
+        let var = unsafe { *envp.add(i) };
+        if var.is_null() {
+            break;
+        }
+        let var = unsafe { CStr::from_ptr(var as *const c_char) }.to_bytes();
+        if let Some(eq) = var.iter().position(|&b| b == b'=').filter(|&eq| eq > 0) {
+            vars.insert(UnixString::from_vec(var[..eq].to_vec()), UnixString::from_vec(var[eq + 1..].to_vec()));
+        }
+    }
+    let pwd = vars.get(UnixStr::new("PWD")).map(Path::new).filter(|dir| dir.has_root()).map(fs::normalize_lexically);
+    if let Some(dir) = pwd {
+        *CURRENT_DIR.try_lock().expect("current directory is being changed") = Some(dir);
+    }
+    *VARS.try_lock().expect(BUSY_VARS) = vars;
+}
+
+fn read_args_file() -> Vec<UnixString> {
+    let Ok(contents) = fs::read(ARGS_FILE) else {
+        return Vec::new();
+    };
+    // a process that read it at the same time and removed it first owns it,
+    // any other failure to remove it leaves it to everyone
+    if fs::remove_file(ARGS_FILE).is_err_and(|err| err.kind() == ErrorKind::NotFound) {
+        return Vec::new();
+    }
+    let contents = contents.strip_suffix(b"\n").unwrap_or(&contents);
+    if contents.is_empty() {
+        return Vec::new();
+    }
+    contents.split(|&b| b == b'\n').map(|arg| UnixString::from_vec(arg.to_vec())).collect()
+}
+
+// the file is read without holding the lock, a mount serving it may ask for the arguments too
+fn load_args() -> Vec<UnixString> {
+    if let Some(args) = &*ARGS.try_lock().expect(BUSY_ARGS) {
+        return args.clone();
+    }
+    let read = read_args_file();
+    ARGS.try_lock().expect(BUSY_ARGS).get_or_insert(read).clone()
+}
+
+/// The arguments the process was started with, the program first.
+///
+/// The iterator panics on an argument that isn't valid UTF-8, see [`args_os`].
+pub fn args() -> Args {
+    Args { inner: args_os() }
+}
+
+/// The arguments the process was started with as raw [`UnixString`]s.
+pub fn args_os() -> ArgsOs {
+    ArgsOs { inner: load_args().into_iter() }
+}
+
+/// Iterator over the arguments as `String`s, made by [`args`].
+#[derive(Debug)]
+pub struct Args {
+    inner: ArgsOs,
+}
+
+impl Iterator for Args {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.inner.next().map(|arg| arg.into_string().expect("argument isn't valid UTF-8"))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for Args {
+    fn next_back(&mut self) -> Option<String> {
+        self.inner.next_back().map(|arg| arg.into_string().expect("argument isn't valid UTF-8"))
+    }
+}
+
+impl ExactSizeIterator for Args {}
+
+/// Iterator over the arguments, made by [`args_os`].
+#[derive(Debug)]
+pub struct ArgsOs {
+    inner: vec::IntoIter<UnixString>,
+}
+
+impl Iterator for ArgsOs {
+    type Item = UnixString;
+
+    fn next(&mut self) -> Option<UnixString> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for ArgsOs {
+    fn next_back(&mut self) -> Option<UnixString> {
+        self.inner.next_back()
+    }
+}
+
+impl ExactSizeIterator for ArgsOs {}
+
+/// Why [`var`] found no value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VarError {
+    NotPresent,
+    NotUnicode(UnixString),
+}
+
+impl fmt::Display for VarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotPresent => f.write_str("environment variable not found"),
+            Self::NotUnicode(value) => write!(f, "environment variable was not valid unicode: {:?}", value),
+        }
+    }
+}
+
+impl core::error::Error for VarError {}
+
+fn lock_vars() -> crate::sync::TryLockGuard<'static, BTreeMap<UnixString, UnixString>> {
+    VARS.try_lock().expect(BUSY_VARS)
+}
+
+/// The value of the variable `key` if it's set and valid UTF-8.
+pub fn var<K: AsRef<UnixStr>>(key: K) -> Result<String, VarError> {
+    var_os(key).ok_or(VarError::NotPresent)?.into_string().map_err(VarError::NotUnicode)
+}
+
+pub fn var_os<K: AsRef<UnixStr>>(key: K) -> Option<UnixString> {
+    lock_vars().get(key.as_ref()).cloned()
+}
+
+/// Sets the variable `key`, replacing what it was.
+///
+/// Panics if `key` is empty or contains `=` or a NUL, or `value` contains a NUL.
+pub fn set_var<K: AsRef<UnixStr>, V: AsRef<UnixStr>>(key: K, value: V) {
+    let (key, value) = (key.as_ref(), value.as_ref());
+    assert!(
+        !key.is_empty() && !key.as_bytes().iter().any(|&b| b == b'=' || b == 0) && !value.as_bytes().contains(&0),
+        "invalid environment variable {:?}={:?}", key, value,
+    );
+    lock_vars().insert(key.to_unix_string(), value.to_unix_string());
+}
+
+pub fn remove_var<K: AsRef<UnixStr>>(key: K) {
+    lock_vars().remove(key.as_ref());
+}
+
+/// The variables set when this is called, sorted by name.
+///
+/// The iterator panics on a name or value that isn't valid UTF-8, see [`vars_os`].
+pub fn vars() -> Vars {
+    Vars { inner: vars_os() }
+}
+
+pub fn vars_os() -> VarsOs {
+    VarsOs { inner: lock_vars().clone().into_iter() }
+}
+
+/// Iterator over the variables as `String`s, made by [`vars`].
+#[derive(Debug)]
+pub struct Vars {
+    inner: VarsOs,
+}
+
+impl Iterator for Vars {
+    type Item = (String, String);
+
+    fn next(&mut self) -> Option<(String, String)> {
+        let (key, value) = self.inner.next()?;
+        let key = key.into_string().expect("environment variable name isn't valid UTF-8");
+        let value = value.into_string().expect("environment variable value isn't valid UTF-8");
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// Iterator over the variables, made by [`vars_os`].
+#[derive(Debug)]
+pub struct VarsOs {
+    inner: btree_map::IntoIter<UnixString, UnixString>,
+}
+
+impl Iterator for VarsOs {
+    type Item = (UnixString, UnixString);
+
+    fn next(&mut self) -> Option<(UnixString, UnixString)> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
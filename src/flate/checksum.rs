@@ -0,0 +1,71 @@
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { 0xEDB8_8320 ^ (crc >> 1) } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+// the CRC-32 zip and gzip use
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Crc32 {
+    value: u32,
+}
+
+impl Crc32 {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        let mut crc = !self.value;
+        for &b in data {
+            crc = CRC_TABLE[((crc ^ b as u32) & 0xFF) as usize] ^ (crc >> 8);
+        }
+        self.value = !crc;
+    }
+
+    pub(crate) fn value(&self) -> u32 {
+        self.value
+    }
+}
+
+// the Adler-32 zlib uses
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Adler32 {
+    a: u32,
+    b: u32,
+}
+
+impl Adler32 {
+    const MOD: u32 = 65521;
+    // the most bytes that can be summed before `b` could overflow
+    const CHUNK: usize = 5552;
+
+    pub(crate) fn new() -> Self {
+        Self { a: 1, b: 0 }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        for chunk in data.chunks(Self::CHUNK) {
+            for &byte in chunk {
+                self.a += byte as u32;
+                self.b += self.a;
+            }
+            self.a %= Self::MOD;
+            self.b %= Self::MOD;
+        }
+    }
+
+    pub(crate) fn value(&self) -> u32 {
+        (self.b << 16) | self.a
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{flate::Inflater, io::{self, Read}};
+
+/// Decompresses a raw DEFLATE (RFC 1951) stream.
+///
+/// The decoder reads ahead of the end of the compressed data, whatever follows
+/// it in the inner reader can't be read from there anymore.
+#[derive(Debug)]
+pub struct DeflateDecoder<R> {
+    inner: Inflater<R>,
+}
+
+impl<R: Read> DeflateDecoder<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner: Inflater::new(inner) }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        self.inner.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        self.inner.get_mut()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+}
+
+impl<R: Read> Read for DeflateDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
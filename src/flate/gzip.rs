@@ -0,0 +1,110 @@
+use crate::{flate::{Crc32, Inflater, Stage}, io::{self, Error, ErrorKind, Read}};
+
+const FHCRC: u8 = 0x02;
+const FEXTRA: u8 = 0x04;
+const FNAME: u8 = 0x08;
+const FCOMMENT: u8 = 0x10;
+
+const INVALID_HEADER: Error = Error::const_new(ErrorKind::InvalidData, "invalid gzip header");
+
+/// Decompresses a gzip (RFC 1952) member and checks its CRC-32 and length at the end.
+///
+/// Only the first member is read, anything concatenated after it is ignored.
+#[derive(Debug)]
+pub struct GzDecoder<R> {
+    inner: Inflater<R>,
+    stage: Stage,
+    crc: Crc32,
+    // the length modulo 2^32, as the trailer has it
+    len: u32,
+}
+
+impl<R: Read> GzDecoder<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner: Inflater::new(inner), stage: Stage::Header, crc: Crc32::new(), len: 0 }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        self.inner.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        self.inner.get_mut()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+
+    fn read_header(&mut self) -> io::Result<()> {
+        // the header has its own optional CRC over everything before it
+        let mut crc = Crc32::new();
+        let mut fixed = [0; 10];
+        self.read_header_bytes(&mut fixed, &mut crc)?;
+        if fixed[..3] != [0x1F, 0x8B, 8] || fixed[3] & 0xE0 != 0 {
+            return Err(INVALID_HEADER);
+        }
+        let flags = fixed[3];
+
+        if flags & FEXTRA != 0 {
+            let mut len = [0; 2];
+            self.read_header_bytes(&mut len, &mut crc)?;
+            for _ in 0..u16::from_le_bytes(len) {
+                self.read_header_bytes(&mut [0], &mut crc)?;
+            }
+        }
+        for flag in [FNAME, FCOMMENT] {
+            if flags & flag != 0 {
+                let mut byte = [1];
+                while byte[0] != 0 {
+                    self.read_header_bytes(&mut byte, &mut crc)?;
+                }
+            }
+        }
+        if flags & FHCRC != 0 {
+            let mut expected = [0; 2];
+            self.inner.read_aligned(&mut expected)?;
+            if u16::from_le_bytes(expected) != crc.value() as u16 {
+                return Err(INVALID_HEADER);
+            }
+        }
+        Ok(())
+    }
+
+    fn read_header_bytes(&mut self, buf: &mut [u8], crc: &mut Crc32) -> io::Result<()> {
+        self.inner.read_aligned(buf)?;
+        crc.update(buf);
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for GzDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.stage {
+            Stage::Header => {
+                self.read_header()?;
+                self.stage = Stage::Body;
+            },
+            Stage::Body => {},
+            Stage::Done => return Ok(0),
+        }
+
+        let n = self.inner.read(buf)?;
+        self.crc.update(&buf[..n]);
+        self.len = self.len.wrapping_add(n as u32);
+        if n == 0 && !buf.is_empty() {
+            let mut trailer = [0; 8];
+            self.inner.read_aligned(&mut trailer)?;
+            let crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+            let len = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+            if crc != self.crc.value() {
+                return Err(Error::const_new(ErrorKind::InvalidData, "gzip stream fails its CRC-32 check"));
+            }
+            if len != self.len {
+                return Err(Error::const_new(ErrorKind::InvalidData, "gzip stream has a different length than recorded"));
+            }
+            self.stage = Stage::Done;
+        }
+        Ok(n)
+    }
+}
@@ -0,0 +1,349 @@
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use crate::io::{self, Error, ErrorKind, Read};
+
+const WINDOW_SIZE: usize = 1 << 15;
+const MAX_BITS: usize = 15;
+
+const LENGTH_BASE: [u16; 29] = [3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131, 163, 195, 227, 258];
+const LENGTH_EXTRA: [u8; 29] = [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0];
+const DIST_BASE: [u16; 30] = [1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537, 2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577];
+const DIST_EXTRA: [u8; 30] = [0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13, 13];
+// the order code length code lengths come in, RFC 1951 3.2.7
+const CODE_LENGTH_ORDER: [usize; 19] = [16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15];
+
+const fn invalid(msg: &'static str) -> Error {
+    Error::const_new(ErrorKind::InvalidData, msg)
+}
+
+const EARLY_EOF: Error = Error::const_new(ErrorKind::UnexpectedEof, "deflate stream ends early");
+
+// a canonical Huffman code as the number of codes of each length and the
+// symbols ordered by code
+#[derive(Debug)]
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> io::Result<Self> {
+        let mut counts = [0; MAX_BITS + 1];
+        for &len in lengths {
+            counts[len as usize] += 1;
+        }
+        counts[0] = 0;
+
+        // incomplete codes are fine, the unused codes just fail to decode
+        let mut left = 1i32;
+        for &count in &counts[1..] {
+            left = (left << 1) - count as i32;
+            if left < 0 {
+                return Err(invalid("over-subscribed huffman code"));
+            }
+        }
+
+        let mut offsets = [0; MAX_BITS + 1];
+        for len in 1..MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+        let mut symbols = vec![0; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[offsets[len as usize] as usize] = symbol as u16;
+                offsets[len as usize] += 1;
+            }
+        }
+        Ok(Self { counts, symbols })
+    }
+
+    fn fixed() -> (Self, Self) {
+        let mut lengths = [0; 288];
+        lengths[..144].fill(8);
+        lengths[144..256].fill(9);
+        lengths[256..280].fill(7);
+        lengths[280..].fill(8);
+        // neither can be over-subscribed
+        (Self::new(&lengths).unwrap(), Self::new(&[5; 30]).unwrap())
+    }
+}
+
+// reads the input least significant bit first, buffering so a File isn't asked for single bytes
+#[derive(Debug)]
+struct BitReader<R> {
+    inner: R,
+    buf: Box<[u8]>,
+    pos: usize,
+    len: usize,
+    bits: u32,
+    count: u32,
+}
+
+impl<R: Read> BitReader<R> {
+    fn byte(&mut self) -> io::Result<u8> {
+        if self.pos == self.len {
+            self.len = self.inner.read(&mut self.buf)?;
+            self.pos = 0;
+            if self.len == 0 {
+                return Err(EARLY_EOF);
+            }
+        }
+        self.pos += 1;
+        Ok(self.buf[self.pos - 1])
+    }
+
+    fn bits(&mut self, n: u32) -> io::Result<u32> {
+        while self.count < n {
+            self.bits |= (self.byte()? as u32) << self.count;
+            self.count += 8;
+        }
+        let value = self.bits & ((1 << n) - 1);
+        self.bits >>= n;
+        self.count -= n;
+        Ok(value)
+    }
+
+    // drops the bits left in the current byte
+    fn align(&mut self) {
+        let drop = self.count % 8;
+        self.bits >>= drop;
+        self.count -= drop;
+    }
+
+    // reads whole bytes, only after `align`
+    fn read_bytes(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.count > 0 {
+            out[0] = self.bits(8)? as u8;
+            return Ok(1);
+        }
+        if self.pos == self.len {
+            self.byte()?;
+            self.pos -= 1;
+        }
+        let n = out.len().min(self.len - self.pos);
+        out[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+
+    fn decode(&mut self, code: &Huffman) -> io::Result<u16> {
+        // walks the code one bit at a time, `first` is the first code of the current length
+        let (mut value, mut first, mut index) = (0i32, 0i32, 0i32);
+        for &count in &code.counts[1..] {
+            value |= self.bits(1)? as i32;
+            let count = count as i32;
+            if value - first < count {
+                return Ok(code.symbols[(index + value - first) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            value <<= 1;
+        }
+        Err(invalid("invalid huffman code"))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum State {
+    // at a block header
+    Block,
+    Stored(usize),
+    Codes,
+    Copy { len: usize, dist: usize },
+    Done,
+}
+
+// a raw DEFLATE (RFC 1951) stream decoder, reads ahead of the end of the stream
+#[derive(Debug)]
+pub(crate) struct Inflater<R> {
+    input: BitReader<R>,
+    window: Box<[u8]>,
+    // next write position in `window` and how much of it holds output
+    pos: usize,
+    filled: usize,
+    state: State,
+    last: bool,
+    lit: Huffman,
+    dist: Huffman,
+}
+
+impl<R: Read> Inflater<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        let (lit, dist) = Huffman::fixed();
+        Self {
+            input: BitReader { inner, buf: vec![0; 4096].into(), pos: 0, len: 0, bits: 0, count: 0 },
+            window: vec![0; WINDOW_SIZE].into(),
+            pos: 0,
+            filled: 0,
+            state: State::Block,
+            last: false,
+            lit,
+            dist,
+        }
+    }
+
+    pub(crate) fn get_ref(&self) -> &R {
+        &self.input.inner
+    }
+
+    pub(crate) fn get_mut(&mut self) -> &mut R {
+        &mut self.input.inner
+    }
+
+    pub(crate) fn into_inner(self) -> R {
+        self.input.inner
+    }
+
+    // whole bytes from outside the compressed data, like a header before it or a trailer after it
+    pub(crate) fn read_aligned(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.input.align();
+        let mut filled = 0;
+        while filled < buf.len() {
+            filled += self.input.read_bytes(&mut buf[filled..])?;
+        }
+        Ok(())
+    }
+
+    fn emit(&mut self, out: &mut [u8], n: &mut usize, b: u8) {
+        out[*n] = b;
+        *n += 1;
+        self.window[self.pos] = b;
+        self.pos = (self.pos + 1) % WINDOW_SIZE;
+        self.filled = (self.filled + 1).min(WINDOW_SIZE);
+    }
+
+    fn start_block(&mut self) -> io::Result<()> {
+        if self.last {
+            self.state = State::Done;
+            return Ok(());
+        }
+        self.last = self.input.bits(1)? == 1;
+        self.state = match self.input.bits(2)? {
+            0 => {
+                self.input.align();
+                let len = self.input.bits(16)?;
+                if self.input.bits(16)? != !len & 0xFFFF {
+                    return Err(invalid("stored block length doesn't match its complement"));
+                }
+                State::Stored(len as usize)
+            },
+            1 => {
+                (self.lit, self.dist) = Huffman::fixed();
+                State::Codes
+            },
+            2 => {
+                self.read_dynamic()?;
+                State::Codes
+            },
+            _ => return Err(invalid("invalid block type")),
+        };
+        Ok(())
+    }
+
+    fn read_dynamic(&mut self) -> io::Result<()> {
+        let lit_count = self.input.bits(5)? as usize + 257;
+        let dist_count = self.input.bits(5)? as usize + 1;
+        let code_count = self.input.bits(4)? as usize + 4;
+        if lit_count > 286 || dist_count > 30 {
+            return Err(invalid("too many length or distance codes"));
+        }
+
+        let mut lengths = [0; 19];
+        for &i in &CODE_LENGTH_ORDER[..code_count] {
+            lengths[i] = self.input.bits(3)? as u8;
+        }
+        let code = Huffman::new(&lengths)?;
+
+        let mut lengths = [0; 286 + 30];
+        let total = lit_count + dist_count;
+        let mut i = 0;
+        while i < total {
+            let symbol = self.input.decode(&code)?;
+            let (len, repeat) = match symbol {
+                0..=15 => (symbol as u8, 1),
+                16 => match i {
+                    0 => return Err(invalid("repeated length without a previous one")),
+                    _ => (lengths[i - 1], 3 + self.input.bits(2)? as usize),
+                },
+                17 => (0, 3 + self.input.bits(3)? as usize),
+                _ => (0, 11 + self.input.bits(7)? as usize),
+            };
+            if i + repeat > total {
+                return Err(invalid("too many code lengths"));
+            }
+            lengths[i..i + repeat].fill(len);
+            i += repeat;
+        }
+        if lengths[256] == 0 {
+            return Err(invalid("no end of block code"));
+        }
+        self.lit = Huffman::new(&lengths[..lit_count])?;
+        self.dist = Huffman::new(&lengths[lit_count..total])?;
+        Ok(())
+    }
+
+    fn inflate(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        let mut n = 0;
+        while n < out.len() {
+            match self.state {
+                State::Block => self.start_block()?,
+                State::Stored(0) => self.state = State::Block,
+                State::Stored(left) => {
+                    let want = left.min(out.len() - n);
+                    let read = self.input.read_bytes(&mut out[n..n + want])?;
+                    for &b in &out[n..n + read] {
+                        self.window[self.pos] = b;
+                        self.pos = (self.pos + 1) % WINDOW_SIZE;
+                    }
+                    self.filled = (self.filled + read).min(WINDOW_SIZE);
+                    n += read;
+                    self.state = State::Stored(left - read);
+                },
+                State::Codes => {
+                    let symbol = self.input.decode(&self.lit)? as usize;
+                    if symbol < 256 {
+                        self.emit(out, &mut n, symbol as u8);
+                        continue;
+                    }
+                    if symbol == 256 {
+                        self.state = State::Block;
+                        continue;
+                    }
+                    let symbol = symbol - 257;
+                    if symbol >= LENGTH_BASE.len() {
+                        return Err(invalid("invalid length code"));
+                    }
+                    let len = LENGTH_BASE[symbol] as usize + self.input.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+                    let symbol = self.input.decode(&self.dist)? as usize;
+                    if symbol >= DIST_BASE.len() {
+                        return Err(invalid("invalid distance code"));
+                    }
+                    let dist = DIST_BASE[symbol] as usize + self.input.bits(DIST_EXTRA[symbol] as u32)? as usize;
+                    if dist > self.filled {
+                        return Err(invalid("distance reaches before the start of the output"));
+                    }
+                    self.state = State::Copy { len, dist };
+                },
+                State::Copy { len, dist } => {
+                    let count = len.min(out.len() - n);
+                    for _ in 0..count {
+                        let b = self.window[(self.pos + WINDOW_SIZE - dist) % WINDOW_SIZE];
+                        self.emit(out, &mut n, b);
+                    }
+                    self.state = match len - count {
+                        0 => State::Codes,
+                        len => State::Copy { len, dist },
+                    };
+                },
+                State::Done => break,
+            }
+        }
+        Ok(n)
+    }
+}
+
+impl<R: Read> Read for Inflater<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inflate(buf)
+    }
+}
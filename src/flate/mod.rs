@@ -0,0 +1,31 @@
+//! Decompression of DEFLATE data and the zlib and gzip formats wrapped around it.
+//!
+//! The decoders read compressed data from any [`Read`](crate::io::Read) and are
+//! themselves a `Read` of the decompressed data:
+//!
+//! ```ignore
+//! let mut level = String::new();
+//! GzDecoder::new(File::open("/assets/level1.txt.gz")?).read_to_string(&mut level)?;
+//! ```
+//!
+//! Checksum mismatches and malformed data are reported as [`ErrorKind::InvalidData`](crate::io::ErrorKind::InvalidData),
+//! compressed data that is cut short as [`ErrorKind::UnexpectedEof`](crate::io::ErrorKind::UnexpectedEof).
+
+mod inflate;
+pub(crate) use inflate::*;
+mod checksum;
+pub(crate) use checksum::*;
+mod deflate;
+pub use deflate::*;
+mod zlib;
+pub use zlib::*;
+mod gzip;
+pub use gzip::*;
+
+// where a wrapped stream is, the header is read on the first `read`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Header,
+    Body,
+    Done,
+}
@@ -0,0 +1,67 @@
+use crate::{flate::{Adler32, Inflater, Stage}, io::{self, Error, ErrorKind, Read}};
+
+/// Decompresses a zlib (RFC 1950) stream and checks its Adler-32 at the end.
+///
+/// Streams that need a preset dictionary aren't supported.
+#[derive(Debug)]
+pub struct ZlibDecoder<R> {
+    inner: Inflater<R>,
+    stage: Stage,
+    adler: Adler32,
+}
+
+impl<R: Read> ZlibDecoder<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner: Inflater::new(inner), stage: Stage::Header, adler: Adler32::new() }
+    }
+
+    pub fn get_ref(&self) -> &R {
+        self.inner.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        self.inner.get_mut()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner.into_inner()
+    }
+
+    fn read_header(&mut self) -> io::Result<()> {
+        let mut header = [0; 2];
+        self.inner.read_aligned(&mut header)?;
+        let [cmf, flg] = header;
+        if cmf & 0x0F != 8 || cmf >> 4 > 7 || u16::from_be_bytes(header) % 31 != 0 {
+            return Err(Error::const_new(ErrorKind::InvalidData, "invalid zlib header"));
+        }
+        if flg & 0x20 != 0 {
+            return Err(Error::const_new(ErrorKind::Unsupported, "zlib preset dictionaries aren't supported"));
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for ZlibDecoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.stage {
+            Stage::Header => {
+                self.read_header()?;
+                self.stage = Stage::Body;
+            },
+            Stage::Body => {},
+            Stage::Done => return Ok(0),
+        }
+
+        let n = self.inner.read(buf)?;
+        self.adler.update(&buf[..n]);
+        if n == 0 && !buf.is_empty() {
+            let mut trailer = [0; 4];
+            self.inner.read_aligned(&mut trailer)?;
+            if u32::from_be_bytes(trailer) != self.adler.value() {
+                return Err(Error::const_new(ErrorKind::InvalidData, "zlib stream fails its Adler-32 check"));
+            }
+            self.stage = Stage::Done;
+        }
+        Ok(n)
+    }
+}
@@ -0,0 +1,173 @@
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+use core::ffi::c_char;
+
+use alloc::{ffi::CString, vec, vec::{IntoIter, Vec}};
+use path::{Path, PathBuf};
+use unix_str::UnixString;
+
+use crate::{fs::{self, path_to_cstring, vfs, FileType, FsResult, Metadata}, io::{self, Error, ErrorKind}};
+
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+unsafe extern "C" {
+    // fills `buf` with as many entries as fit starting at entry `*offset` and moves
+    // `*offset` past them, each entry is a kind byte followed by the NUL-terminated name.
+    // Returns `FsResult::BufferTooSmall` if not even the first entry fits
+    #[link_name = "dir_list"]
+    pub unsafe fn ffi_dir_list(path: *const c_char, buf: *mut c_char, size: u64, offset: *mut u64) -> i32;
+}
+
+#[cfg(feature = "mock")]
+pub use crate::mock::fs::ffi_dir_list;
+
+#[cfg(not(feature = "proposed-abi"))]
+pub use crate::proposed::ffi_dir_list;
+
+const LIST_BUF_SIZE: usize = 4096;
+// an entry that needs more than this is taken as a broken driver
+const MAX_LIST_BUF_SIZE: usize = 1 << 20;
+
+// a directory listed through RedactedOS
+#[derive(Debug)]
+pub(crate) struct OsReadDir {
+    dir: PathBuf,
+    raw_path: CString,
+    offset: u64,
+    buf: Vec<u8>,
+    pos: usize,
+    // entries in `buf` not handed out yet
+    left: u64,
+    done: bool,
+}
+
+impl OsReadDir {
+    pub(crate) fn open(dir: &Path) -> io::Result<Self> {
+        let raw_path = path_to_cstring(dir)?;
+        let mut read_dir = Self { dir: dir.to_path_buf(), raw_path, offset: 0, buf: vec![0; LIST_BUF_SIZE], pos: 0, left: 0, done: false };
+        // errors like a missing directory come out here instead of from the first `next`
+        read_dir.fill()?;
+        Ok(read_dir)
+    }
+
+    fn fill(&mut self) -> io::Result<()> {
+        let before = self.offset;
+        loop {
+            let code = unsafe {
+                ffi_dir_list(self.raw_path.as_ptr(), self.buf.as_mut_ptr() as _, self.buf.len() as u64, &mut self.offset as _)
+            };
+            if code != FsResult::BufferTooSmall as i32 {
+                FsResult::check(code)?;
+                break;
+            }
+            if self.buf.len() >= MAX_LIST_BUF_SIZE {
+                return Err(Error::const_new(ErrorKind::InvalidData, "directory entry too long"));
+            }
+            self.buf.resize(self.buf.len() * 2, 0);
+        }
+        self.left = self.offset.checked_sub(before)
+            .ok_or(Error::const_new(ErrorKind::InvalidData, "directory listing went backwards"))?;
+        self.pos = 0;
+        self.done = self.left == 0;
+        Ok(())
+    }
+
+    fn next_entry(&mut self) -> io::Result<DirEntry> {
+        const MALFORMED: Error = Error::const_new(ErrorKind::InvalidData, "malformed directory listing");
+        let (&kind, rest) = self.buf[self.pos..].split_first().ok_or(MALFORMED)?;
+        let len = rest.iter().position(|&b| b == 0).ok_or(MALFORMED)?;
+        let name = &rest[..len];
+        self.pos += len + 2;
+        self.left -= 1;
+
+        let ty = FileType::from_raw(kind).ok_or(Error::const_new(ErrorKind::InvalidData, "unknown file type in directory listing"))?;
+        Ok(DirEntry { path: self.dir.join(Path::new(unix_str::UnixStr::from_bytes(name))), ty })
+    }
+}
+
+impl Iterator for OsReadDir {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        if self.left == 0 {
+            if self.done {
+                return None;
+            }
+            if let Err(err) = self.fill() {
+                self.done = true;
+                return Some(Err(err));
+            }
+            if self.done {
+                return None;
+            }
+        }
+
+        let entry = self.next_entry();
+        if entry.is_err() {
+            // can't tell where the next entry starts anymore
+            self.left = 0;
+            self.done = true;
+        }
+        Some(entry)
+    }
+}
+
+#[derive(Debug)]
+enum Repr {
+    Os(OsReadDir),
+    Vfs(IntoIter<DirEntry>),
+}
+
+/// Iterator over a directory, made by [`read_dir`].
+#[derive(Debug)]
+pub struct ReadDir {
+    repr: Repr,
+}
+
+impl Iterator for ReadDir {
+    type Item = io::Result<DirEntry>;
+
+    fn next(&mut self) -> Option<io::Result<DirEntry>> {
+        match &mut self.repr {
+            Repr::Os(read_dir) => read_dir.next(),
+            Repr::Vfs(entries) => entries.next().map(Ok),
+        }
+    }
+}
+
+/// One entry of a [`ReadDir`].
+#[derive(Debug, Clone)]
+pub struct DirEntry {
+    path: PathBuf,
+    ty: FileType,
+}
+
+impl DirEntry {
+    /// The directory that was listed joined with the entry's name.
+    pub fn path(&self) -> PathBuf {
+        self.path.clone()
+    }
+
+    pub fn file_name(&self) -> UnixString {
+        self.path.file_name().map(|name| name.to_unix_string()).unwrap_or_default()
+    }
+
+    pub fn file_type(&self) -> io::Result<FileType> {
+        Ok(self.ty)
+    }
+
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        fs::metadata(&self.path)
+    }
+}
+
+/// Lists the entries of the directory at `path`, without `.` and `..`.
+pub fn read_dir<P: AsRef<Path>>(path: P) -> io::Result<ReadDir> {
+    let dir = path.as_ref();
+    let repr = match vfs::lookup(dir)? {
+        Some((fs, rest)) => {
+            let entries: Vec<_> = fs.read_dir(&rest)?.into_iter().map(|entry| DirEntry { path: dir.join(&entry.name), ty: entry.file_type }).collect();
+            Repr::Vfs(entries.into_iter())
+        },
+        None => Repr::Os(OsReadDir::open(dir)?),
+    };
+    Ok(ReadDir { repr })
+}
@@ -1,19 +1,25 @@
-use core::{ffi::c_char, u64};
+#[cfg(not(feature = "mock"))]
+use core::ffi::c_char;
+use core::sync::atomic::{AtomicU64, Ordering};
 
-use alloc::ffi::CString;
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use bitmask_enum::bitmask;
 use path::Path;
 
-use crate::{io::{self, Error, ErrorKind, Read, Seek, SeekFrom}};
+use crate::{fs::{ffi_fstat, path_to_cstring, vfs::{self, VfsFile}, FileType, Metadata, OpenOptions, RawMetadata}, io::{self, BorrowedCursor, Error, ErrorKind, IoSliceMut, Read, Seek, SeekFrom, Write}};
 
 #[derive(Debug, Default, Clone, Copy)]
 #[repr(C)]
 pub struct FileDescriptor {
-    id: u64,
-    size: u64,
-    cursor: u64,
+    pub(crate) id: u64,
+    pub(crate) size: u64,
+    pub(crate) cursor: u64,
 }
 
 impl FileDescriptor {
+    /// # Safety
+    ///
+    /// `fd_id` has to be a descriptor id RedactedOS handed out and `size` its real size.
     pub unsafe fn from_raw(size: u64, fd_id: u64) -> FileDescriptor {
         FileDescriptor { id: fd_id, size, cursor: 0 }
     }
@@ -23,18 +29,108 @@ impl FsResult {
     pub fn to_io_error(self) -> Option<Error> {
         match self {
             Self::Success => None,
-            Self::NotFound => Some(Error::os_error(ErrorKind::NotFound)),
-            Self::DriverError => Some(Error::const_new(ErrorKind::Other, "Driver Error")),
+            err => Some(err.into()),
+        }
+    }
+
+    // turns a code straight from the C side into a result, unknown codes included
+    pub(crate) fn check(code: i32) -> io::Result<()> {
+        match Self::try_from(code) {
+            Ok(res) => res.into(),
+            Err(code) => Err(Error::from_raw_os_error(code)),
+        }
+    }
+
+    pub(crate) fn decode_error_kind(code: i32) -> ErrorKind {
+        if code == UNSUPPORTED {
+            return ErrorKind::Unsupported;
+        }
+        match Self::try_from(code) {
+            Ok(Self::NotFound) => ErrorKind::NotFound,
+            Ok(Self::ReadOnly) => ErrorKind::ReadOnlyFilesystem,
+            Ok(Self::AlreadyExists) => ErrorKind::AlreadyExists,
+            Ok(Self::NotADirectory) => ErrorKind::NotADirectory,
+            Ok(Self::IsADirectory) => ErrorKind::IsADirectory,
+            Ok(Self::DirectoryNotEmpty) => ErrorKind::DirectoryNotEmpty,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    pub(crate) fn error_string(code: i32) -> &'static str {
+        if code == UNSUPPORTED {
+            return "not supported by this RedactedOS";
+        }
+        match Self::try_from(code) {
+            Ok(Self::Success) => "success",
+            Ok(Self::NotFound) => "no such file or directory",
+            Ok(Self::DriverError) => "driver error",
+            Ok(Self::ReadOnly) => "read-only file system",
+            Ok(Self::AlreadyExists) => "file exists",
+            Ok(Self::NotADirectory) => "not a directory",
+            Ok(Self::IsADirectory) => "is a directory",
+            Ok(Self::DirectoryNotEmpty) => "directory not empty",
+            Ok(Self::BufferTooSmall) => "buffer too small",
+            Err(_) => "unknown error",
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
-#[repr(C)]
+impl TryFrom<i32> for FsResult {
+    type Error = i32;
+
+    fn try_from(code: i32) -> Result<Self, i32> {
+        match code {
+            0 => Ok(Self::Success),
+            1 => Ok(Self::NotFound),
+            2 => Ok(Self::DriverError),
+            3 => Ok(Self::ReadOnly),
+            4 => Ok(Self::AlreadyExists),
+            5 => Ok(Self::NotADirectory),
+            6 => Ok(Self::IsADirectory),
+            7 => Ok(Self::DirectoryNotEmpty),
+            8 => Ok(Self::BufferTooSmall),
+            code => Err(code),
+        }
+    }
+}
+
+impl From<FsResult> for io::Result<()> {
+    fn from(value: FsResult) -> Self {
+        match value.to_io_error() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+// what the `crate::proposed` stand-ins return, RedactedOS never does
+pub(crate) const UNSUPPORTED: i32 = i32::MIN;
+
+// fopen hands this back as a plain int, see `FsResult::check`. RedactedOS only
+// returns 0 to 2 so far, the rest are what the `proposed-abi` symbols report
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
 pub enum FsResult {
-    Success,
-    NotFound,
-    DriverError,
+    Success = 0,
+    NotFound = 1,
+    DriverError = 2,
+    ReadOnly = 3,
+    AlreadyExists = 4,
+    NotADirectory = 5,
+    IsADirectory = 6,
+    DirectoryNotEmpty = 7,
+    BufferTooSmall = 8,
+}
+
+#[bitmask(u32)]
+#[derive(Default)]
+pub enum OpenFlags {
+    READ,
+    WRITE,
+    APPEND,
+    CREATE,
+    TRUNCATE,
+    CREATE_NEW,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -52,9 +148,10 @@ fn seek_to_ffi_parts(seek_from: SeekFrom, size: u64) -> (i64, SeekType) {
     }
 }
 
+#[cfg(not(feature = "mock"))]
 unsafe extern "C" {
     #[link_name = "fopen"]
-    pub unsafe fn ffi_fopen(path: *const c_char, descriptor: *mut FileDescriptor) -> FsResult;
+    pub unsafe fn ffi_fopen(path: *const c_char, descriptor: *mut FileDescriptor) -> i32;
     #[link_name = "fclose"]
     pub unsafe fn ffi_fclose(descriptor: *const FileDescriptor);
     #[link_name = "seek"]
@@ -63,77 +160,375 @@ unsafe extern "C" {
     pub unsafe fn ffi_fread(descriptor: *mut FileDescriptor, buf: *mut c_char, size: u64) -> u64;
 }
 
-#[derive(Debug, Clone)]
-pub struct File {
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+unsafe extern "C" {
+    #[link_name = "fopen_mode"]
+    pub unsafe fn ffi_fopen_mode(path: *const c_char, flags: OpenFlags, descriptor: *mut FileDescriptor) -> i32;
+    #[link_name = "fwrite"]
+    pub unsafe fn ffi_fwrite(descriptor: *mut FileDescriptor, buf: *const c_char, size: u64) -> u64;
+    #[link_name = "fsync"]
+    pub unsafe fn ffi_fsync(descriptor: *const FileDescriptor) -> i32;
+}
+
+#[cfg(not(feature = "proposed-abi"))]
+pub use crate::proposed::{ffi_fopen_mode, ffi_fwrite, ffi_fsync};
+
+#[cfg(feature = "mock")]
+pub use crate::mock::fs::{ffi_fopen, ffi_fopen_mode, ffi_fclose, ffi_fseek, ffi_fread, ffi_fwrite, ffi_fsync};
+
+// most `read_to_end` reserves at once before any of it is read
+const READ_TO_END_CHUNK: usize = 1 << 20;
+
+// closes the descriptor once the last `try_clone`d file is gone
+#[derive(Debug)]
+struct Handle {
     descriptor: FileDescriptor,
+    // the size as of the last write through any clone
+    size: AtomicU64,
 }
 
-impl File {
-    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+impl Drop for Handle {
+    fn drop(&mut self) {
+        unsafe { ffi_fclose(&self.descriptor as _); }
+    }
+}
+
+// what an `OsFile` turned out to be, streams always report a size of 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Shape {
+    Sized,
+    Stream,
+    // empty and fstat couldn't tell, read like a stream but still seekable
+    Unknown,
+}
+
+// a file opened through RedactedOS
+#[derive(Debug)]
+pub(crate) struct OsFile {
+    // own copy so every clone keeps its own cursor
+    descriptor: FileDescriptor,
+    handle: Arc<Handle>,
+    flags: OpenFlags,
+    // device files have no size or position, reads take whatever is there.
+    // `None` until `shape` had to find out
+    shape: Option<Shape>,
+}
+
+impl OsFile {
+    pub(crate) fn open(path: &Path, flags: OpenFlags) -> io::Result<Self> {
         unsafe {
-            let path = CString::from_vec_unchecked(path.as_ref().as_unix_str().as_bytes().to_vec());
+            let path = path_to_cstring(path)?;
             let mut descriptor = FileDescriptor::default();
-            if let Some(err) = ffi_fopen(path.as_ptr(), &mut descriptor as _).to_io_error() {
-                return Err(err);
-            }
-            if descriptor.size == 0 {
-                let mut buf = [0u8; 1];
-                if ffi_fread(&mut descriptor as _, buf.as_mut_ptr(), 1) == 1 { // virtual unsized files
-                    descriptor.size = u64::MAX;
-                }
+            // plain reads keep using fopen so they work the same as they always have
+            if flags == OpenFlags::READ {
+                FsResult::check(ffi_fopen(path.as_ptr(), &mut descriptor as _))?;
+            } else {
+                FsResult::check(ffi_fopen_mode(path.as_ptr(), flags, &mut descriptor as _))?;
             }
-            Ok(Self { descriptor })
+            let handle = Arc::new(Handle { descriptor, size: AtomicU64::new(descriptor.size) });
+            Ok(Self { descriptor, handle, flags, shape: None })
         }
     }
 
-    pub fn size(&self) -> usize {
-        self.descriptor.size as usize
+    // picks up writes made through other clones
+    fn refresh_size(&mut self) {
+        self.descriptor.size = self.handle.size.load(Ordering::Relaxed);
     }
 
-    pub unsafe fn fd_id(&self) -> u64 {
-        self.descriptor.id
+    // fclose can't fail, so what can still go wrong is getting written data onto the disk
+    fn close(self) -> io::Result<()> {
+        let synced = match self.flags.intersects(OpenFlags::WRITE | OpenFlags::APPEND) {
+            true => self.sync_all(),
+            false => Ok(()),
+        };
+        drop(self);
+        synced
     }
 
-    pub fn close(self) {
-        unsafe { ffi_fclose(&self.descriptor as _);}
+    // only empty files need an fstat. If that fails they're read like a stream,
+    // which is what an empty file reads like anyway, until they get some data
+    fn shape(&mut self) -> Shape {
+        self.refresh_size();
+        match self.shape {
+            Some(Shape::Unknown) | None if self.descriptor.size != 0 => *self.shape.insert(Shape::Sized),
+            Some(shape) => shape,
+            None => {
+                let mut raw = RawMetadata::default();
+                let shape = match unsafe { FsResult::check(ffi_fstat(&self.descriptor as _, &mut raw as _)) } {
+                    Ok(()) if FileType::from_raw(raw.kind).is_some_and(|ty| ty.is_stream()) => Shape::Stream,
+                    Ok(()) => Shape::Sized,
+                    Err(_) => Shape::Unknown,
+                };
+                *self.shape.insert(shape)
+            },
+        }
     }
-}
 
-impl Read for File {
-    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    // fread only ever writes, so `buf` doesn't need to be initialized
+    unsafe fn read_raw(&mut self, buf: *mut u8, len: usize) -> usize {
         unsafe {
-            if self.descriptor.size == u64::MAX {
-                self.descriptor.cursor = 0;
+            if self.shape() != Shape::Sized {
+                return ffi_fread(&mut self.descriptor as _, buf as _, len as u64) as usize;
             }
+            self.refresh_size();
             if self.descriptor.cursor == self.descriptor.size {
-                return Ok(0);
+                return 0;
             }
-            let mut size = (buf.len() as u64).min(u64::MAX);
+            let mut size = len as u64;
             if self.descriptor.cursor + size > self.descriptor.size {
                 size = self.descriptor.size - self.descriptor.cursor;
             }
-            let ret = ffi_fread(&mut self.descriptor as _, buf.as_ptr() as _, size);
-            Ok(ret as usize)
+            ffi_fread(&mut self.descriptor as _, buf as _, size) as usize
         }
     }
 }
 
-impl Seek for File {
+impl VfsFile for OsFile {
+    // without fstat all there is to go on is the size, so a stream looks like an empty file
+    fn metadata(&self) -> io::Result<Metadata> {
+        let mut raw = RawMetadata::default();
+        match unsafe { FsResult::check(ffi_fstat(&self.descriptor as _, &mut raw as _)) } {
+            Err(err) if err.kind() == ErrorKind::Unsupported => {
+                let size = self.handle.size.load(Ordering::Relaxed);
+                Metadata::from_raw(RawMetadata { size, kind: 1, readonly: 0 })
+            },
+            res => {
+                res?;
+                Metadata::from_raw(raw)
+            },
+        }
+    }
+
+    fn try_clone(&self) -> io::Result<Box<dyn VfsFile>> {
+        Ok(Box::new(Self { descriptor: self.descriptor, handle: self.handle.clone(), flags: self.flags, shape: self.shape }))
+    }
+
+    fn sync_all(&self) -> io::Result<()> {
+        unsafe { FsResult::check(ffi_fsync(&self.descriptor as _)) }
+    }
+}
+
+impl Read for OsFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        unsafe { Ok(self.read_raw(buf.as_mut_ptr(), buf.len())) }
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let mut read = 0;
+        for buf in bufs {
+            let n = unsafe { self.read_raw(buf.as_mut_ptr(), buf.len()) };
+            read += n;
+            if n < buf.len() {
+                break;
+            }
+        }
+        Ok(read)
+    }
+
+    // sized files go straight into the vec, usually with a single fread
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        if self.shape() != Shape::Sized {
+            return io::default_read_to_end(self, buf);
+        }
+        let start = buf.len();
+        loop {
+            if buf.len() == buf.capacity() {
+                // the size comes from the driver, so it only decides the first chunks
+                let remaining = self.descriptor.size.saturating_sub(self.descriptor.cursor);
+                let additional = (remaining.min(READ_TO_END_CHUNK as u64) as usize).max(32);
+                buf.try_reserve(additional).map_err(|_| Error::from(ErrorKind::OutOfMemory))?;
+            }
+            unsafe {
+                let len = buf.len();
+                let n = self.read_raw(buf.as_mut_ptr().add(len), buf.capacity() - len);
+                if n == 0 {
+                    return Ok(len - start);
+                }
+                buf.set_len(len + n);
+            }
+        }
+    }
+
+    fn read_buf(&mut self, mut cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        unsafe {
+            let buf = cursor.as_mut();
+            let n = self.read_raw(buf.as_mut_ptr() as _, buf.len());
+            cursor.advance(n);
+        }
+        Ok(())
+    }
+}
+
+impl Write for OsFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.flags.intersects(OpenFlags::WRITE | OpenFlags::APPEND) {
+            return Err(Error::const_new(ErrorKind::PermissionDenied, "file not opened for writing"));
+        }
+        self.refresh_size();
+        let written = unsafe { ffi_fwrite(&mut self.descriptor as _, buf.as_ptr() as _, buf.len() as u64) as usize };
+        self.handle.size.store(self.descriptor.size, Ordering::Relaxed);
+        Ok(written)
+    }
+
+    // nothing is buffered on our side, see `sync_all` for getting data onto the disk
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for OsFile {
     fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        if self.shape() == Shape::Stream {
+            return Err(Error::NOT_SEEKABLE);
+        }
+        self.refresh_size();
         unsafe {
             let (offset, ty) = seek_to_ffi_parts(pos, self.descriptor.size);
             ffi_fseek(&mut self.descriptor as _, offset, ty);
             self.stream_position()
         }
     }
-    
+
     fn stream_position(&mut self) -> io::Result<u64> {
+        if self.shape() == Shape::Stream {
+            return Err(Error::NOT_SEEKABLE);
+        }
         Ok(self.descriptor.cursor)
     }
 }
 
-impl Drop for File {
-    fn drop(&mut self) {
-        unsafe { ffi_fclose(&self.descriptor as _); }
+#[derive(Debug)]
+enum Repr {
+    Os(OsFile),
+    // anything opened through a mount in `fs::vfs`
+    Vfs(Box<dyn VfsFile>),
+}
+
+#[derive(Debug)]
+pub struct File {
+    repr: Repr,
+}
+
+impl File {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        OpenOptions::new().read(true).open(path)
+    }
+
+    /// Opens `path` for writing, creating it or emptying what was there.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        OpenOptions::new().write(true).create(true).truncate(true).open(path)
+    }
+
+    pub fn options() -> OpenOptions {
+        OpenOptions::new()
+    }
+
+    pub(crate) fn open_with(path: &Path, flags: OpenFlags) -> io::Result<Self> {
+        let repr = match vfs::lookup(path)? {
+            Some((fs, rest)) => Repr::Vfs(fs.open(&rest, flags)?),
+            None => Repr::Os(OsFile::open(path, flags)?),
+        };
+        Ok(Self { repr })
+    }
+
+    fn inner(&self) -> &dyn VfsFile {
+        match &self.repr {
+            Repr::Os(file) => file,
+            Repr::Vfs(file) => &**file,
+        }
+    }
+
+    fn inner_mut(&mut self) -> &mut dyn VfsFile {
+        match &mut self.repr {
+            Repr::Os(file) => file,
+            Repr::Vfs(file) => &mut **file,
+        }
+    }
+
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        self.inner().metadata()
+    }
+
+    /// Makes sure everything written so far has reached the storage device.
+    pub fn sync_all(&self) -> io::Result<()> {
+        self.inner().sync_all()
     }
-}
\ No newline at end of file
+
+    pub fn size(&self) -> usize {
+        match &self.repr {
+            Repr::Os(file) => file.handle.size.load(Ordering::Relaxed) as usize,
+            Repr::Vfs(file) => file.metadata().map_or(0, |meta| meta.len() as usize),
+        }
+    }
+
+    /// The RedactedOS descriptor id, `u64::MAX` for files that come from a VFS mount.
+    ///
+    /// # Safety
+    ///
+    /// The id must not be closed or moved past the cursor behind this file's back.
+    pub unsafe fn fd_id(&self) -> u64 {
+        match &self.repr {
+            Repr::Os(file) => file.descriptor.id,
+            Repr::Vfs(_) => u64::MAX,
+        }
+    }
+
+    /// Another `File` for the same open descriptor, starting at the same position
+    /// but moving independently. The descriptor is closed when all of them are.
+    pub fn try_clone(&self) -> io::Result<Self> {
+        let repr = match &self.repr {
+            Repr::Os(file) => Repr::Os(OsFile { descriptor: file.descriptor, handle: file.handle.clone(), flags: file.flags, shape: file.shape }),
+            Repr::Vfs(file) => Repr::Vfs(file.try_clone()?),
+        };
+        Ok(Self { repr })
+    }
+
+    /// Closes the file, clones made with `try_clone` stay open.
+    ///
+    /// Unlike dropping it, this reports whether what was written made it to the
+    /// storage device, like [`sync_all`](Self::sync_all).
+    pub fn close(self) -> io::Result<()> {
+        match self.repr {
+            Repr::Os(file) => file.close(),
+            Repr::Vfs(file) => file.sync_all(),
+        }
+    }
+}
+
+impl Read for File {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner_mut().read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.inner_mut().read_vectored(bufs)
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        self.inner_mut().read_to_end(buf)
+    }
+
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        self.inner_mut().read_buf(cursor)
+    }
+}
+
+impl Write for File {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner_mut().flush()
+    }
+}
+
+impl Seek for File {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner_mut().seek(pos)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        self.inner_mut().stream_position()
+    }
+}
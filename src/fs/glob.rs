@@ -0,0 +1,271 @@
+use core::fmt;
+
+use alloc::{string::String, vec::Vec};
+use path::{Path, PathBuf};
+
+use crate::{fs::{self, WalkDirIter}, io::{self, Error, ErrorKind}};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Char(char),
+    // `?`
+    AnyChar,
+    // `*`
+    AnySeq,
+    // `[...]`, ranges are inclusive and single characters are `(c, c)`
+    Class { negated: bool, ranges: Vec<(char, char)> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    // `**` on its own, any number of components
+    Recursive,
+    Tokens(Vec<Token>),
+}
+
+/// Where and why a glob pattern couldn't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternError {
+    pub pos: usize,
+    pub msg: &'static str,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid glob pattern at {}: {}", self.pos, self.msg)
+    }
+}
+
+impl core::error::Error for PatternError {}
+
+/// A compiled glob pattern matched against whole paths.
+///
+/// `?` matches one character and `*` any number of them, neither crosses a `/`.
+/// `[abc]`, `[a-z]` and `[!abc]` match one character from (or not from) a set, and
+/// a component that is just `**` matches any number of directories.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    original: String,
+    absolute: bool,
+    parts: Vec<Part>,
+}
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, PatternError> {
+        let mut parts = Vec::new();
+        let mut pos = 0;
+        for component in pattern.split('/') {
+            match component {
+                "" | "." => {},
+                "**" => {
+                    // `a/**/**/b` is the same as `a/**/b`
+                    if parts.last() != Some(&Part::Recursive) {
+                        parts.push(Part::Recursive);
+                    }
+                },
+                _ => parts.push(Part::Tokens(parse_component(component, pos)?)),
+            }
+            pos += component.chars().count() + 1;
+        }
+        Ok(Self { original: pattern.into(), absolute: pattern.starts_with('/'), parts })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.original
+    }
+
+    /// Matches the components of `path`, `.` components and repeated slashes are ignored.
+    pub fn matches_path(&self, path: &Path) -> bool {
+        let path = path.as_unix_str().to_string_lossy();
+        if path.starts_with('/') != self.absolute {
+            return false;
+        }
+        let components: Vec<Vec<char>> = path.split('/').filter(|c| !c.is_empty() && *c != ".").map(|c| c.chars().collect()).collect();
+        match_parts(&self.parts, &components)
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        self.matches_path(Path::new(path))
+    }
+
+    // the leading components without wildcards, where a search can start
+    fn literal_prefix(&self) -> (PathBuf, &[Part]) {
+        let mut prefix = PathBuf::from(if self.absolute { "/" } else { "" });
+        for (i, part) in self.parts.iter().enumerate() {
+            let Part::Tokens(tokens) = part else {
+                return (prefix, &self.parts[i..]);
+            };
+            let literal: Option<String> = tokens.iter().map(|token| match token {
+                Token::Char(c) => Some(*c),
+                _ => None,
+            }).collect();
+            match literal {
+                Some(literal) => prefix.push(literal),
+                None => return (prefix, &self.parts[i..]),
+            }
+        }
+        (prefix, &[])
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.original)
+    }
+}
+
+fn parse_component(component: &str, start: usize) -> Result<Vec<Token>, PatternError> {
+    let chars: Vec<char> = component.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            '?' => tokens.push(Token::AnyChar),
+            '*' => {
+                if tokens.last() != Some(&Token::AnySeq) {
+                    tokens.push(Token::AnySeq);
+                }
+            },
+            '[' => {
+                let open = i;
+                i += 1;
+                let negated = chars.get(i) == Some(&'!');
+                if negated {
+                    i += 1;
+                }
+                let mut ranges = Vec::new();
+                // a `]` right at the start is part of the set
+                let mut first = true;
+                loop {
+                    let Some(&c) = chars.get(i) else {
+                        return Err(PatternError { pos: start + open, msg: "unclosed character class" });
+                    };
+                    if c == ']' && !first {
+                        break;
+                    }
+                    first = false;
+                    if chars.get(i + 1) == Some(&'-') && chars.get(i + 2).is_some_and(|&end| end != ']') {
+                        let end = chars[i + 2];
+                        if end < c {
+                            return Err(PatternError { pos: start + i, msg: "invalid character range" });
+                        }
+                        ranges.push((c, end));
+                        i += 3;
+                    } else {
+                        ranges.push((c, c));
+                        i += 1;
+                    }
+                }
+                tokens.push(Token::Class { negated, ranges });
+            },
+            c => tokens.push(Token::Char(c)),
+        }
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+// `**` is to components what `*` is to characters, both go through `match_seq`
+fn match_parts(parts: &[Part], components: &[Vec<char>]) -> bool {
+    match_seq(parts, components, |part| *part == Part::Recursive, |part, component| match part {
+        Part::Tokens(tokens) => match_tokens(tokens, component),
+        Part::Recursive => unreachable!(),
+    })
+}
+
+fn match_tokens(tokens: &[Token], chars: &[char]) -> bool {
+    match_seq(tokens, chars, |token| *token == Token::AnySeq, match_char)
+}
+
+// wildcard matching that only ever goes back to the last star, which is enough
+// because a later star can cover anything an earlier one would have. Takes
+// O(pattern * input) instead of backtracking into every star
+fn match_seq<P, I>(pattern: &[P], input: &[I], is_star: impl Fn(&P) -> bool, matches: impl Fn(&P, &I) -> bool) -> bool {
+    let (mut p, mut i) = (0, 0);
+    // the last star and the input position it was tried at
+    let mut star = None;
+    while i < input.len() {
+        match pattern.get(p) {
+            Some(item) if is_star(item) => {
+                star = Some((p, i));
+                p += 1;
+            },
+            Some(item) if matches(item, &input[i]) => {
+                p += 1;
+                i += 1;
+            },
+            _ => match star {
+                Some((star_p, star_i)) => {
+                    star = Some((star_p, star_i + 1));
+                    p = star_p + 1;
+                    i = star_i + 1;
+                },
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(is_star)
+}
+
+fn match_char(token: &Token, &c: &char) -> bool {
+    match token {
+        Token::Char(expected) => *expected == c,
+        Token::AnyChar => true,
+        Token::AnySeq => unreachable!(),
+        Token::Class { negated, ranges } => ranges.iter().any(|&(start, end)| (start..=end).contains(&c)) != *negated,
+    }
+}
+
+/// Iterator over the paths matching a pattern, made by [`glob`].
+#[derive(Debug)]
+pub struct Glob {
+    pattern: Pattern,
+    walk: Option<WalkDirIter>,
+    // the walk started at `.` for a relative pattern, which isn't part of the results
+    strip_dot: bool,
+}
+
+impl Iterator for Glob {
+    type Item = io::Result<PathBuf>;
+
+    fn next(&mut self) -> Option<io::Result<PathBuf>> {
+        loop {
+            let entry = match self.walk.as_mut()?.next()? {
+                Ok(entry) => entry,
+                Err(err) => return Some(Err(err)),
+            };
+            // `.` itself has nothing left once stripped
+            if (self.strip_dot && entry.depth() == 0) || !self.pattern.matches_path(entry.path()) {
+                continue;
+            }
+            let path = entry.into_path();
+            if self.strip_dot {
+                return Some(Ok(path.strip_prefix(".").map(Path::to_path_buf).unwrap_or(path)));
+            }
+            return Some(Ok(path));
+        }
+    }
+}
+
+/// Finds the paths matching `pattern` (see [`Pattern`]), sorted by name within each directory.
+///
+/// Only the part of the tree below the pattern's wildcard-free prefix is walked,
+/// and only as deep as the pattern can reach.
+pub fn glob(pattern: &str) -> io::Result<Glob> {
+    let pattern = Pattern::new(pattern).map_err(|err| Error::from_error(ErrorKind::InvalidInput, err))?;
+    let (mut root, rest) = pattern.literal_prefix();
+    let strip_dot = root.as_unix_str().is_empty();
+    if strip_dot {
+        root = PathBuf::from(".");
+    }
+    let max_depth = match rest.contains(&Part::Recursive) {
+        true => usize::MAX,
+        false => rest.len(),
+    };
+
+    let walk = match fs::exists(&root)? {
+        true => Some(fs::walk_dir(root).max_depth(max_depth).sorted(true).into_iter()),
+        false => None,
+    };
+    Ok(Glob { pattern, walk, strip_dot })
+}
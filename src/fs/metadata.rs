@@ -0,0 +1,141 @@
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+use core::ffi::c_char;
+
+use path::Path;
+
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+use crate::fs::FileDescriptor;
+use crate::{fs::{path_to_cstring, vfs, FsResult}, io::{self, Error, ErrorKind}};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum FileKind {
+    Regular,
+    Directory,
+    Stream,
+}
+
+/// What kind of node a path points to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FileType(FileKind);
+
+impl FileType {
+    pub const FILE: Self = Self(FileKind::Regular);
+    pub const DIR: Self = Self(FileKind::Directory);
+    pub const STREAM: Self = Self(FileKind::Stream);
+
+    pub fn is_file(&self) -> bool {
+        self.0 == FileKind::Regular
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.0 == FileKind::Directory
+    }
+
+    /// Device files without a size, like the ones under `/dev`.
+    pub fn is_stream(&self) -> bool {
+        self.0 == FileKind::Stream
+    }
+
+    // kind byte as RedactedOS writes it
+    pub(crate) fn from_raw(kind: u8) -> Option<Self> {
+        match kind {
+            1 => Some(Self(FileKind::Regular)),
+            2 => Some(Self(FileKind::Directory)),
+            3 => Some(Self(FileKind::Stream)),
+            _ => None,
+        }
+    }
+}
+
+/// Metadata as `stat` and `fstat` write it, the kind byte is the same as in directory listings.
+#[doc(hidden)]
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+pub struct RawMetadata {
+    pub size: u64,
+    pub kind: u8,
+    pub readonly: u8,
+}
+
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+unsafe extern "C" {
+    #[link_name = "stat"]
+    pub unsafe fn ffi_stat(path: *const c_char, metadata: *mut RawMetadata) -> i32;
+    #[link_name = "fstat"]
+    pub unsafe fn ffi_fstat(descriptor: *const FileDescriptor, metadata: *mut RawMetadata) -> i32;
+}
+
+#[cfg(feature = "mock")]
+pub use crate::mock::fs::{ffi_stat, ffi_fstat};
+
+#[cfg(not(feature = "proposed-abi"))]
+pub use crate::proposed::{ffi_stat, ffi_fstat};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Permissions {
+    readonly: bool,
+}
+
+impl Permissions {
+    pub fn readonly(&self) -> bool {
+        self.readonly
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Metadata {
+    len: u64,
+    ty: FileType,
+    permissions: Permissions,
+}
+
+impl Metadata {
+    /// For [`FileSystem`](crate::fs::vfs::FileSystem) implementations.
+    pub fn new(len: u64, ty: FileType, readonly: bool) -> Self {
+        Self { len, ty, permissions: Permissions { readonly } }
+    }
+
+    pub(crate) fn from_raw(raw: RawMetadata) -> io::Result<Self> {
+        let ty = FileType::from_raw(raw.kind).ok_or(Error::const_new(ErrorKind::InvalidData, "unknown file type"))?;
+        Ok(Self { len: raw.size, ty, permissions: Permissions { readonly: raw.readonly != 0 } })
+    }
+
+    /// Size in bytes, streams always report 0.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.ty
+    }
+
+    pub fn is_file(&self) -> bool {
+        self.ty.is_file()
+    }
+
+    pub fn is_dir(&self) -> bool {
+        self.ty.is_dir()
+    }
+
+    pub fn permissions(&self) -> Permissions {
+        self.permissions
+    }
+}
+
+pub fn metadata<P: AsRef<Path>>(path: P) -> io::Result<Metadata> {
+    match vfs::lookup(path.as_ref())? {
+        Some((fs, rest)) => fs.metadata(&rest),
+        None => metadata_os(path.as_ref()),
+    }
+}
+
+pub(crate) fn metadata_os(path: &Path) -> io::Result<Metadata> {
+    let path = path_to_cstring(path)?;
+    let mut raw = RawMetadata::default();
+    unsafe { FsResult::check(ffi_stat(path.as_ptr(), &mut raw as _))?; }
+    Metadata::from_raw(raw)
+}
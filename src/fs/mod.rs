@@ -1,2 +1,162 @@
+//! Files and directories on the RedactedOS filesystem, plus [`vfs`] mounts.
+//!
+//! Reading goes through `fopen`, `fread`, `seek` and `fclose`, which RedactedOS
+//! exports today. Everything else expects these calls, only linked with the
+//! `proposed-abi` feature. Each returns an [`FsResult`] code unless noted:
+//!
+//! - `fopen_mode(path, flags, descriptor)` opens with [`OpenFlags`] bits.
+//! - `fwrite(descriptor, buf, size)` returns the number of bytes written.
+//! - `fsync(descriptor)` writes out what's cached for the file.
+//! - `stat(path, metadata)` and `fstat(descriptor, metadata)` fill a [`RawMetadata`].
+//! - `dir_list(path, buf, size, offset)` fills `buf` with entries from `*offset`
+//!   on, each a kind byte and a NUL-terminated name, and moves `*offset` past them.
+//!   [`FsResult::BufferTooSmall`] means not even the first entry fit.
+//! - `mkdir(path)`, `rmdir(path)`, `unlink(path)` and `rename(from, to)`.
+//!
+//! Kind bytes are 1 for a file, 2 for a directory and 3 for a stream. Paths are
+//! absolute and NUL-terminated.
+
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+use core::ffi::c_char;
+
+use alloc::{ffi::CString, string::String, vec::Vec};
+use path::Path;
+
+use crate::io::{self, Error, ErrorKind, Read, Write};
+
 mod file;
-pub use file::*;
\ No newline at end of file
+pub use file::*;
+mod open_options;
+pub use open_options::*;
+mod dir;
+pub use dir::*;
+mod metadata;
+pub use metadata::*;
+mod walk;
+pub use walk::*;
+mod glob;
+pub use glob::*;
+mod normalize;
+pub use normalize::*;
+pub mod vfs;
+
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+unsafe extern "C" {
+    #[link_name = "mkdir"]
+    pub unsafe fn ffi_mkdir(path: *const c_char) -> i32;
+    #[link_name = "rmdir"]
+    pub unsafe fn ffi_rmdir(path: *const c_char) -> i32;
+    #[link_name = "unlink"]
+    pub unsafe fn ffi_unlink(path: *const c_char) -> i32;
+    #[link_name = "rename"]
+    pub unsafe fn ffi_rename(from: *const c_char, to: *const c_char) -> i32;
+}
+
+#[cfg(feature = "mock")]
+pub use crate::mock::fs::{ffi_mkdir, ffi_rmdir, ffi_unlink, ffi_rename};
+
+#[cfg(not(feature = "proposed-abi"))]
+pub use crate::proposed::{ffi_mkdir, ffi_rmdir, ffi_unlink, ffi_rename};
+
+// every path goes through here on its way to the C side, relative ones get resolved first
+pub(crate) fn path_to_cstring(path: &Path) -> io::Result<CString> {
+    Ok(CString::new(absolute(path)?.as_unix_str().as_bytes())?)
+}
+
+/// Reads the whole file at `path`, sized files take a single allocation and `fread`.
+pub fn read<P: AsRef<Path>>(path: P) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    File::open(path)?.read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+pub fn read_to_string<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    String::from_utf8(read(path)?).map_err(|_| Error::INVALID_UTF8)
+}
+
+/// Replaces the contents of the file at `path`, creating it if needed.
+pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(path: P, contents: C) -> io::Result<()> {
+    File::create(path)?.write_all(contents.as_ref())
+}
+
+pub fn create_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path_to_cstring(path.as_ref())?;
+    unsafe { FsResult::check(ffi_mkdir(path.as_ptr())) }
+}
+
+/// Creates `path` and any missing parents, fine if it already is a directory.
+pub fn create_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if path.as_unix_str().is_empty() {
+        return Ok(());
+    }
+    match create_dir(path) {
+        Ok(()) => return Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => {},
+        Err(_) if metadata_os(path).is_ok_and(|meta| meta.is_dir()) => return Ok(()),
+        Err(err) => return Err(err),
+    }
+    match path.parent() {
+        Some(parent) => create_dir_all(parent)?,
+        None => return Err(Error::const_new(ErrorKind::Other, "failed to create whole tree")),
+    }
+    match create_dir(path) {
+        Err(_) if metadata_os(path).is_ok_and(|meta| meta.is_dir()) => Ok(()),
+        res => res,
+    }
+}
+
+pub fn remove_file<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path_to_cstring(path.as_ref())?;
+    unsafe { FsResult::check(ffi_unlink(path.as_ptr())) }
+}
+
+/// Removes an empty directory.
+pub fn remove_dir<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path_to_cstring(path.as_ref())?;
+    unsafe { FsResult::check(ffi_rmdir(path.as_ptr())) }
+}
+
+/// Removes a directory with everything in it.
+///
+/// Like removing single files this only looks at the RedactedOS filesystem,
+/// anything mounted in or over `path` is left alone.
+pub fn remove_dir_all<P: AsRef<Path>>(path: P) -> io::Result<()> {
+    let path = path.as_ref();
+    if !metadata_os(path)?.is_dir() {
+        return Err(ErrorKind::NotADirectory.into());
+    }
+    // listing goes by index, so removing while iterating would skip entries
+    let entries = OsReadDir::open(path)?.collect::<io::Result<Vec<_>>>()?;
+    for entry in entries {
+        if entry.file_type()?.is_dir() {
+            remove_dir_all(entry.path())?;
+        } else {
+            remove_file(entry.path())?;
+        }
+    }
+    remove_dir(path)
+}
+
+/// Moves `from` to `to`, replacing `to` if it is a file.
+pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<()> {
+    let from = path_to_cstring(from.as_ref())?;
+    let to = path_to_cstring(to.as_ref())?;
+    unsafe { FsResult::check(ffi_rename(from.as_ptr(), to.as_ptr())) }
+}
+
+/// Copies the contents of `from` into `to`, returning the number of bytes copied.
+pub fn copy<P: AsRef<Path>, Q: AsRef<Path>>(from: P, to: Q) -> io::Result<u64> {
+    let mut reader = File::open(from)?;
+    let mut writer = File::create(to)?;
+    io::copy(&mut reader, &mut writer)
+}
+
+/// Whether `path` points at something, errors other than `NotFound` are passed on.
+pub fn exists<P: AsRef<Path>>(path: P) -> io::Result<bool> {
+    match metadata(path) {
+        Ok(_) => Ok(true),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(err),
+    }
+}
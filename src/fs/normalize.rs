@@ -0,0 +1,61 @@
+use path::{Component, Path, PathBuf};
+
+use crate::{env, fs::{self, File}, io::{self, ErrorKind}};
+
+/// Resolves `.` and `..` without looking at the filesystem.
+///
+/// `..` at the root stays at the root, leading `..` of a relative path are kept
+/// since there is nothing to resolve them against.
+pub fn normalize_lexically<P: AsRef<Path>>(path: P) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    // `..` that can still be popped instead of kept
+    let mut depth = 0;
+    for component in path.as_ref().components() {
+        match component {
+            Component::RootDir => normalized.push("/"),
+            Component::CurDir => {},
+            Component::ParentDir if depth > 0 => {
+                normalized.pop();
+                depth -= 1;
+            },
+            Component::ParentDir if normalized.has_root() => {},
+            Component::ParentDir => normalized.push(".."),
+            Component::Normal(name) => {
+                normalized.push(name);
+                depth += 1;
+            },
+        }
+    }
+    if normalized.as_unix_str().is_empty() {
+        normalized.push(".");
+    }
+    normalized
+}
+
+/// Makes `path` absolute against [`env::current_dir`] and normalizes it, whether it exists or not.
+pub fn absolute<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
+    let path = path.as_ref();
+    if path.has_root() {
+        return Ok(normalize_lexically(path));
+    }
+    Ok(normalize_lexically(env::current_dir()?.join(path)))
+}
+
+/// The absolute, normalized form of a path that exists.
+///
+/// RedactedOS has no links, so this is [`absolute`] plus a check that something is there.
+/// Without `stat` that check is opening the path, and only [`ErrorKind::NotFound`]
+/// counts as nothing being there since opening a directory can fail in other ways.
+pub fn canonicalize<P: AsRef<Path>>(path: P) -> io::Result<PathBuf> {
+    let path = absolute(path)?;
+    match fs::metadata(&path) {
+        Err(err) if err.kind() == ErrorKind::Unsupported => match File::open(&path) {
+            Err(err) if err.kind() == ErrorKind::NotFound => return Err(err),
+            _ => {},
+        },
+        res => {
+            res?;
+        },
+    }
+    Ok(path)
+}
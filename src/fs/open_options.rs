@@ -0,0 +1,80 @@
+use path::Path;
+
+use crate::{fs::{File, OpenFlags}, io::{self, Error, ErrorKind}};
+
+/// Picks how a [`File`] gets opened, starting from nothing allowed.
+///
+/// ```ignore
+/// let log = OpenOptions::new().append(true).create(true).open("/save/log.txt")?;
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenOptions {
+    flags: OpenFlags,
+}
+
+impl OpenOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read(&mut self, read: bool) -> &mut Self {
+        self.set(OpenFlags::READ, read)
+    }
+
+    pub fn write(&mut self, write: bool) -> &mut Self {
+        self.set(OpenFlags::WRITE, write)
+    }
+
+    /// Writes always go to the end of the file, implies `write`.
+    pub fn append(&mut self, append: bool) -> &mut Self {
+        self.set(OpenFlags::APPEND, append)
+    }
+
+    /// Empties the file on open, needs `write`.
+    pub fn truncate(&mut self, truncate: bool) -> &mut Self {
+        self.set(OpenFlags::TRUNCATE, truncate)
+    }
+
+    /// Creates the file if it's missing, needs `write` or `append`.
+    pub fn create(&mut self, create: bool) -> &mut Self {
+        self.set(OpenFlags::CREATE, create)
+    }
+
+    /// Creates the file and fails with `AlreadyExists` if it was there, `create`
+    /// and `truncate` are ignored with it.
+    pub fn create_new(&mut self, create_new: bool) -> &mut Self {
+        self.set(OpenFlags::CREATE_NEW, create_new)
+    }
+
+    pub fn open<P: AsRef<Path>>(&self, path: P) -> io::Result<File> {
+        File::open_with(path.as_ref(), self.checked_flags()?)
+    }
+
+    fn set(&mut self, flag: OpenFlags, on: bool) -> &mut Self {
+        if on {
+            self.flags |= flag;
+        } else {
+            self.flags &= !flag;
+        }
+        self
+    }
+
+    // same combinations std rejects
+    fn checked_flags(&self) -> io::Result<OpenFlags> {
+        let mut flags = self.flags;
+        let writes = flags.intersects(OpenFlags::WRITE | OpenFlags::APPEND);
+        if !writes && !flags.contains(OpenFlags::READ) {
+            return Err(Error::const_new(ErrorKind::InvalidInput, "no access mode set"));
+        }
+        if !writes && flags.intersects(OpenFlags::CREATE | OpenFlags::TRUNCATE | OpenFlags::CREATE_NEW) {
+            return Err(Error::const_new(ErrorKind::InvalidInput, "creating or truncating a file needs write access"));
+        }
+        if flags.contains(OpenFlags::APPEND) && flags.contains(OpenFlags::TRUNCATE) && !flags.contains(OpenFlags::CREATE_NEW) {
+            return Err(Error::const_new(ErrorKind::InvalidInput, "can't truncate a file opened for appending"));
+        }
+        if flags.contains(OpenFlags::CREATE_NEW) {
+            flags &= !(OpenFlags::CREATE | OpenFlags::TRUNCATE);
+        }
+        Ok(flags)
+    }
+}
@@ -0,0 +1,49 @@
+use alloc::{boxed::Box, vec::Vec};
+use path::Path;
+
+use crate::{archive::TarArchive, fs::{vfs::{FileSystem, Tree, VfsDirEntry, VfsFile}, Metadata, OpenFlags}, io::{self, Cursor}};
+
+/// A read-only filesystem over a ustar archive baked into the binary, files are
+/// served straight out of the archive without copying.
+///
+/// ```ignore
+/// vfs::mount("/assets", EmbeddedFs::from_tar(include_bytes!("../assets.tar"))?)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct EmbeddedFs {
+    tree: Tree<&'static [u8]>,
+}
+
+impl EmbeddedFs {
+    /// Indexes the archive, regular files and directories are kept and other
+    /// entries like links are left out.
+    pub fn from_tar(archive: &'static [u8]) -> io::Result<Self> {
+        let mut tree = Tree::new();
+        for entry in TarArchive::new(Cursor::new(archive))?.entries() {
+            let key = Tree::<&[u8]>::key(entry.path());
+            match entry.stored_range() {
+                Some(range) if entry.is_file() => {
+                    tree.files.insert(key, &archive[range.start as usize..range.end as usize]);
+                },
+                _ => {
+                    tree.dirs.insert(key);
+                },
+            }
+        }
+        Ok(Self { tree })
+    }
+}
+
+impl FileSystem for EmbeddedFs {
+    fn open(&self, path: &Path, flags: OpenFlags) -> io::Result<Box<dyn VfsFile>> {
+        self.tree.open(path, flags)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.tree.metadata(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<VfsDirEntry>> {
+        self.tree.read_dir(path)
+    }
+}
@@ -0,0 +1,62 @@
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use path::Path;
+
+use crate::{fs::{vfs::{FileSystem, Tree, VfsDirEntry, VfsFile}, Metadata, OpenFlags}, io};
+
+/// A read-only filesystem built up in memory, handy for tests that need the
+/// same files every time.
+///
+/// ```ignore
+/// vfs::mount("/cfg", MemoryFs::new().with_file("settings.ini", "volume=3"))?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct MemoryFs {
+    tree: Tree<Arc<[u8]>>,
+}
+
+impl MemoryFs {
+    pub fn new() -> Self {
+        Self { tree: Tree::new() }
+    }
+
+    /// Adds a file, directories above it appear on their own.
+    pub fn insert<P: AsRef<Path>, D: Into<Vec<u8>>>(&mut self, path: P, data: D) -> &mut Self {
+        self.tree.files.insert(Tree::<Arc<[u8]>>::key(path.as_ref()), data.into().into());
+        self
+    }
+
+    pub fn insert_dir<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
+        self.tree.dirs.insert(Tree::<Arc<[u8]>>::key(path.as_ref()));
+        self
+    }
+
+    pub fn with_file<P: AsRef<Path>, D: Into<Vec<u8>>>(mut self, path: P, data: D) -> Self {
+        self.insert(path, data);
+        self
+    }
+
+    pub fn with_dir<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.insert_dir(path);
+        self
+    }
+}
+
+impl Default for MemoryFs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileSystem for MemoryFs {
+    fn open(&self, path: &Path, flags: OpenFlags) -> io::Result<Box<dyn VfsFile>> {
+        self.tree.open(path, flags)
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        self.tree.metadata(path)
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<VfsDirEntry>> {
+        self.tree.read_dir(path)
+    }
+}
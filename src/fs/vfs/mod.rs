@@ -0,0 +1,221 @@
+//! Filesystems mounted on the Rust side, consulted by [`File::open`](crate::fs::File::open),
+//! [`fs::metadata`](crate::fs::metadata) and [`fs::read_dir`](crate::fs::read_dir) before
+//! anything reaches RedactedOS.
+//!
+//! ```ignore
+//! vfs::mount("/assets", EmbeddedFs::from_tar(include_bytes!("../assets.tar"))?)?;
+//! let logo = fs::read("/assets/logo.png")?;
+//! ```
+//!
+//! Only opening, metadata and listing go through mounts, the other `fs` functions
+//! always act on the RedactedOS filesystem.
+
+use core::fmt;
+
+use alloc::{boxed::Box, collections::{BTreeMap, BTreeSet}, sync::Arc, vec::Vec};
+use path::{Component, Path, PathBuf};
+use unix_str::UnixString;
+
+use crate::{fs::{self, FileType, Metadata, OpenFlags}, io::{self, Cursor, Error, ErrorKind, Read, Seek, SeekFrom, Write}, sync::global};
+
+mod embedded;
+pub use embedded::*;
+mod memory;
+pub use memory::*;
+mod os;
+pub use os::*;
+
+/// A filesystem that can be mounted, paths it gets are relative to its mount
+/// point and empty for the mount point itself.
+pub trait FileSystem: Send + Sync {
+    fn open(&self, path: &Path, flags: OpenFlags) -> io::Result<Box<dyn VfsFile>>;
+    fn metadata(&self, path: &Path) -> io::Result<Metadata>;
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<VfsDirEntry>>;
+}
+
+/// A file opened from a [`FileSystem`], what a [`File`](crate::fs::File) wraps.
+pub trait VfsFile: Read + Write + Seek + fmt::Debug + Send {
+    fn metadata(&self) -> io::Result<Metadata>;
+
+    fn try_clone(&self) -> io::Result<Box<dyn VfsFile>> {
+        Err(Error::const_new(ErrorKind::Unsupported, "file can't be cloned"))
+    }
+
+    fn sync_all(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A name in a directory listed by [`FileSystem::read_dir`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VfsDirEntry {
+    pub name: UnixString,
+    pub file_type: FileType,
+}
+
+type Mounts = Vec<(PathBuf, Arc<dyn FileSystem>)>;
+
+global! {
+    static MOUNTS: Mounts = Vec::new();
+}
+
+const BUSY: Error = Error::const_new(ErrorKind::ResourceBusy, "mount table is being changed");
+
+/// Mounts `fs` at the absolute path `point`, hiding whatever is below it.
+pub fn mount<P: AsRef<Path>, F: FileSystem + 'static>(point: P, fs: F) -> io::Result<()> {
+    let point = fs::absolute(point)?;
+    let mut mounts = MOUNTS.try_lock().ok_or(BUSY)?;
+    if mounts.iter().any(|(mounted, _)| *mounted == point) {
+        return Err(Error::const_new(ErrorKind::AlreadyExists, "something is already mounted there"));
+    }
+    mounts.push((point, Arc::new(fs)));
+    Ok(())
+}
+
+/// Removes the mount at `point`, files already open from it stay usable.
+pub fn unmount<P: AsRef<Path>>(point: P) -> io::Result<()> {
+    let point = fs::absolute(point)?;
+    let mut mounts = MOUNTS.try_lock().ok_or(BUSY)?;
+    let Some(idx) = mounts.iter().position(|(mounted, _)| *mounted == point) else {
+        return Err(Error::const_new(ErrorKind::NotFound, "nothing is mounted there"));
+    };
+    mounts.remove(idx);
+    Ok(())
+}
+
+// the filesystem responsible for `path` and the path inside it, `None` means RedactedOS
+pub(crate) fn lookup(path: &Path) -> io::Result<Option<(Arc<dyn FileSystem>, PathBuf)>> {
+    if MOUNTS.try_lock().ok_or(BUSY)?.is_empty() {
+        return Ok(None);
+    }
+    let path = fs::absolute(path)?;
+    let mounts = MOUNTS.try_lock().ok_or(BUSY)?;
+    let found = mounts.iter()
+        .filter_map(|(point, fs)| Some((point, fs, path.strip_prefix(point).ok()?)))
+        .max_by_key(|(point, _, _)| point.components().count());
+    Ok(found.map(|(_, fs, rest)| (fs.clone(), rest.to_path_buf())))
+}
+
+/// A read-only file over bytes in memory, what [`MemoryFs`] and [`EmbeddedFs`] hand out.
+#[derive(Debug, Clone)]
+pub struct MemoryFile<T> {
+    cursor: Cursor<T>,
+}
+
+impl<T: AsRef<[u8]>> MemoryFile<T> {
+    pub fn new(data: T) -> Self {
+        Self { cursor: Cursor::new(data) }
+    }
+}
+
+impl<T: AsRef<[u8]>> Read for MemoryFile<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.cursor.read(buf)
+    }
+}
+
+impl<T: AsRef<[u8]>> Seek for MemoryFile<T> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.cursor.seek(pos)
+    }
+}
+
+impl<T> Write for MemoryFile<T> {
+    fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+        Err(ErrorKind::ReadOnlyFilesystem.into())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]> + Clone + fmt::Debug + Send + 'static> VfsFile for MemoryFile<T> {
+    fn metadata(&self) -> io::Result<Metadata> {
+        Ok(Metadata::new(self.cursor.get_ref().as_ref().len() as u64, FileType::FILE, true))
+    }
+
+    fn try_clone(&self) -> io::Result<Box<dyn VfsFile>> {
+        Ok(Box::new(self.clone()))
+    }
+}
+
+// files kept in a map, directories are whatever is above a file plus the empty ones added
+#[derive(Debug, Clone)]
+struct Tree<T> {
+    files: BTreeMap<PathBuf, T>,
+    dirs: BTreeSet<PathBuf>,
+}
+
+impl<T: AsRef<[u8]> + Clone + fmt::Debug + Send + Sync + 'static> Tree<T> {
+    fn new() -> Self {
+        Self { files: BTreeMap::new(), dirs: BTreeSet::new() }
+    }
+
+    // paths are stored relative and without `.` or `..`
+    fn key(path: &Path) -> PathBuf {
+        let mut key = PathBuf::new();
+        for component in path.components() {
+            match component {
+                Component::Normal(name) => key.push(name),
+                Component::ParentDir => {
+                    key.pop();
+                },
+                Component::RootDir | Component::CurDir => {},
+            }
+        }
+        key
+    }
+
+    fn is_dir(&self, key: &Path) -> bool {
+        key.as_unix_str().is_empty()
+            || self.dirs.contains(key)
+            || self.files.keys().chain(&self.dirs).any(|path| path != key && path.starts_with(key))
+    }
+
+    fn open(&self, path: &Path, flags: OpenFlags) -> io::Result<Box<dyn VfsFile>> {
+        if flags.intersects(OpenFlags::WRITE | OpenFlags::APPEND | OpenFlags::CREATE | OpenFlags::TRUNCATE | OpenFlags::CREATE_NEW) {
+            return Err(ErrorKind::ReadOnlyFilesystem.into());
+        }
+        let key = Self::key(path);
+        match self.files.get(&key) {
+            Some(data) => Ok(Box::new(MemoryFile::new(data.clone()))),
+            None if self.is_dir(&key) => Err(ErrorKind::IsADirectory.into()),
+            None => Err(ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        let key = Self::key(path);
+        match self.files.get(&key) {
+            Some(data) => Ok(Metadata::new(data.as_ref().len() as u64, FileType::FILE, true)),
+            None if self.is_dir(&key) => Ok(Metadata::new(0, FileType::DIR, true)),
+            None => Err(ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<VfsDirEntry>> {
+        let key = Self::key(path);
+        if self.files.contains_key(&key) {
+            return Err(ErrorKind::NotADirectory.into());
+        }
+        if !self.is_dir(&key) {
+            return Err(ErrorKind::NotFound.into());
+        }
+        let mut children = BTreeMap::new();
+        let paths = self.files.keys().map(|path| (path, false)).chain(self.dirs.iter().map(|path| (path, true)));
+        for (path, dir) in paths {
+            let Ok(rest) = path.strip_prefix(&key) else {
+                continue;
+            };
+            let mut components = rest.components();
+            let Some(Component::Normal(name)) = components.next() else {
+                continue;
+            };
+            let nested = components.next().is_some();
+            let file_type = if dir || nested { FileType::DIR } else { FileType::FILE };
+            children.insert(name.to_unix_string(), file_type);
+        }
+        Ok(children.into_iter().map(|(name, file_type)| VfsDirEntry { name, file_type }).collect())
+    }
+}
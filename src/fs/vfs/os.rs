@@ -0,0 +1,39 @@
+use alloc::{boxed::Box, vec::Vec};
+use path::{Path, PathBuf};
+
+use crate::{fs::{metadata_os, vfs::{FileSystem, VfsDirEntry, VfsFile}, Metadata, OpenFlags, OsFile, OsReadDir}, io};
+
+/// A directory of the RedactedOS filesystem mounted somewhere else.
+///
+/// ```ignore
+/// vfs::mount("/data", OsFs::new("/disk/apps/game"))?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct OsFs {
+    root: PathBuf,
+}
+
+impl OsFs {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf() }
+    }
+}
+
+impl FileSystem for OsFs {
+    fn open(&self, path: &Path, flags: OpenFlags) -> io::Result<Box<dyn VfsFile>> {
+        Ok(Box::new(OsFile::open(&self.root.join(path), flags)?))
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<Metadata> {
+        metadata_os(&self.root.join(path))
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<VfsDirEntry>> {
+        OsReadDir::open(&self.root.join(path))?
+            .map(|entry| {
+                let entry = entry?;
+                Ok(VfsDirEntry { name: entry.file_name(), file_type: entry.file_type()? })
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,228 @@
+use alloc::vec::Vec;
+use path::{Path, PathBuf};
+use unix_str::UnixString;
+
+use crate::{fs::{self, DirEntry, FileType, Metadata}, io};
+
+/// Settings for a recursive walk over a directory tree, see [`walk_dir`].
+///
+/// ```ignore
+/// for entry in fs::walk_dir("/assets").max_depth(2).sorted(true) {
+///     let entry = entry?;
+///     println!("{}", entry.path().display());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct WalkDir {
+    root: PathBuf,
+    min_depth: usize,
+    max_depth: usize,
+    sorted: bool,
+}
+
+impl WalkDir {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self { root: root.as_ref().to_path_buf(), min_depth: 0, max_depth: usize::MAX, sorted: false }
+    }
+
+    /// Entries above this depth are walked through but not yielded, the root is depth 0.
+    pub fn min_depth(mut self, depth: usize) -> Self {
+        self.min_depth = depth;
+        self
+    }
+
+    /// Directories at this depth are yielded but not entered.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Yields the entries of each directory ordered by file name instead of
+    /// in the order RedactedOS lists them.
+    pub fn sorted(mut self, sorted: bool) -> Self {
+        self.sorted = sorted;
+        self
+    }
+}
+
+impl IntoIterator for WalkDir {
+    type Item = io::Result<WalkEntry>;
+    type IntoIter = WalkDirIter;
+
+    fn into_iter(self) -> WalkDirIter {
+        WalkDirIter {
+            root: Some(self.root),
+            pending: None,
+            stack: Vec::new(),
+            min_depth: self.min_depth,
+            max_depth: self.max_depth,
+            sorted: self.sorted,
+        }
+    }
+}
+
+/// Walks a tree with [`walk_dir`], same as `WalkDir::new(root)`.
+pub fn walk_dir<P: AsRef<Path>>(root: P) -> WalkDir {
+    WalkDir::new(root)
+}
+
+/// An entry found by [`WalkDir`].
+#[derive(Debug, Clone)]
+pub struct WalkEntry {
+    path: PathBuf,
+    ty: FileType,
+    depth: usize,
+}
+
+impl WalkEntry {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn into_path(self) -> PathBuf {
+        self.path
+    }
+
+    pub fn file_name(&self) -> UnixString {
+        self.path.file_name().map(|name| name.to_unix_string()).unwrap_or_default()
+    }
+
+    pub fn file_type(&self) -> FileType {
+        self.ty
+    }
+
+    /// How many directories down from the root this is, the root itself is 0.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn metadata(&self) -> io::Result<Metadata> {
+        fs::metadata(&self.path)
+    }
+}
+
+/// Depth-first iterator over a [`WalkDir`], directories come before their contents.
+#[derive(Debug)]
+pub struct WalkDirIter {
+    root: Option<PathBuf>,
+    // directory that was just yielded and gets opened on the next call
+    pending: Option<(PathBuf, usize)>,
+    // entries left in every directory we're inside of, reversed so `pop` is the next one
+    stack: Vec<(usize, Vec<DirEntry>)>,
+    min_depth: usize,
+    max_depth: usize,
+    sorted: bool,
+}
+
+impl WalkDirIter {
+    /// Doesn't descend into the directory that was yielded last, or if that
+    /// wasn't one, skips the rest of the directory it was in.
+    pub fn skip_current_dir(&mut self) {
+        if self.pending.take().is_none() {
+            self.stack.pop();
+        }
+    }
+
+    /// Only yields entries `predicate` accepts, directories it rejects are not entered.
+    pub fn filter_entry<P: FnMut(&WalkEntry) -> bool>(self, predicate: P) -> FilterEntry<P> {
+        FilterEntry { inner: self, predicate }
+    }
+
+    fn open_pending(&mut self) -> io::Result<()> {
+        let Some((dir, depth)) = self.pending.take() else {
+            return Ok(());
+        };
+        if depth >= self.max_depth {
+            return Ok(());
+        }
+        let mut entries = fs::read_dir(&dir)?.collect::<io::Result<Vec<_>>>()?;
+        if self.sorted {
+            entries.sort_by_key(|entry| entry.file_name());
+        }
+        entries.reverse();
+        self.stack.push((depth + 1, entries));
+        Ok(())
+    }
+
+    fn next_entry(&mut self) -> Option<io::Result<WalkEntry>> {
+        if let Some(root) = self.root.take() {
+            let entry = fs::metadata(&root).map(|meta| WalkEntry { path: root, ty: meta.file_type(), depth: 0 });
+            if let Ok(entry) = &entry
+                && entry.ty.is_dir()
+            {
+                self.pending = Some((entry.path.clone(), 0));
+            }
+            return Some(entry);
+        }
+
+        if let Err(err) = self.open_pending() {
+            return Some(Err(err));
+        }
+        loop {
+            let (depth, entries) = self.stack.last_mut()?;
+            let depth = *depth;
+            let Some(entry) = entries.pop() else {
+                self.stack.pop();
+                continue;
+            };
+            let ty = match entry.file_type() {
+                Ok(ty) => ty,
+                Err(err) => return Some(Err(err)),
+            };
+            let entry = WalkEntry { path: entry.path(), ty, depth };
+            if entry.ty.is_dir() {
+                self.pending = Some((entry.path.clone(), depth));
+            }
+            return Some(Ok(entry));
+        }
+    }
+}
+
+impl Iterator for WalkDirIter {
+    type Item = io::Result<WalkEntry>;
+
+    fn next(&mut self) -> Option<io::Result<WalkEntry>> {
+        loop {
+            match self.next_entry()? {
+                Ok(entry) if entry.depth < self.min_depth => continue,
+                res => return Some(res),
+            }
+        }
+    }
+}
+
+/// A [`WalkDirIter`] that leaves out what a predicate rejects, see [`WalkDirIter::filter_entry`].
+#[derive(Debug)]
+pub struct FilterEntry<P> {
+    inner: WalkDirIter,
+    predicate: P,
+}
+
+impl<P> FilterEntry<P> {
+    pub fn skip_current_dir(&mut self) {
+        self.inner.skip_current_dir();
+    }
+}
+
+impl<P: FnMut(&WalkEntry) -> bool> Iterator for FilterEntry<P> {
+    type Item = io::Result<WalkEntry>;
+
+    fn next(&mut self) -> Option<io::Result<WalkEntry>> {
+        loop {
+            let entry = match self.inner.next_entry()? {
+                Ok(entry) => entry,
+                Err(err) => return Some(Err(err)),
+            };
+            if !(self.predicate)(&entry) {
+                if entry.ty.is_dir() {
+                    self.inner.pending = None;
+                }
+                continue;
+            }
+            if entry.depth < self.inner.min_depth {
+                continue;
+            }
+            return Some(Ok(entry));
+        }
+    }
+}
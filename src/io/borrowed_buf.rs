@@ -0,0 +1,188 @@
+use core::{fmt, mem::MaybeUninit};
+
+/// A byte buffer that may start out uninitialized, filled in by [`Read::read_buf`](crate::io::Read::read_buf).
+///
+/// It tracks two lengths: the filled part at the front and the part known to be
+/// initialized, which is never shorter. Readers that write through raw pointers
+/// can fill it without anyone zeroing the memory first.
+pub struct BorrowedBuf<'data> {
+    buf: &'data mut [MaybeUninit<u8>],
+    filled: usize,
+    init: usize,
+}
+
+impl<'data> From<&'data mut [u8]> for BorrowedBuf<'data> {
+    fn from(slice: &'data mut [u8]) -> Self {
+        let init = slice.len();
+        // an initialized slice is a valid uninit one, and nothing uninit gets written through it
+        let buf = unsafe { &mut *(slice as *mut [u8] as *mut [MaybeUninit<u8>]) };
+        Self { buf, filled: 0, init }
+    }
+}
+
+impl<'data> From<&'data mut [MaybeUninit<u8>]> for BorrowedBuf<'data> {
+    fn from(buf: &'data mut [MaybeUninit<u8>]) -> Self {
+        Self { buf, filled: 0, init: 0 }
+    }
+}
+
+impl fmt::Debug for BorrowedBuf<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BorrowedBuf")
+            .field("init", &self.init)
+            .field("filled", &self.filled)
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<'data> BorrowedBuf<'data> {
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Length of the filled part.
+    pub fn len(&self) -> usize {
+        self.filled
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.filled == 0
+    }
+
+    /// Length of the initialized part, filled or not.
+    pub fn init_len(&self) -> usize {
+        self.init
+    }
+
+    pub fn filled(&self) -> &[u8] {
+        unsafe { &*(&self.buf[..self.filled] as *const [MaybeUninit<u8>] as *const [u8]) }
+    }
+
+    pub fn filled_mut(&mut self) -> &mut [u8] {
+        unsafe { &mut *(&mut self.buf[..self.filled] as *mut [MaybeUninit<u8>] as *mut [u8]) }
+    }
+
+    pub fn into_filled(self) -> &'data [u8] {
+        unsafe { &*(&self.buf[..self.filled] as *const [MaybeUninit<u8>] as *const [u8]) }
+    }
+
+    /// A cursor for writing into the unfilled part.
+    pub fn unfilled(&mut self) -> BorrowedCursor<'_> {
+        BorrowedCursor {
+            start: self.filled,
+            buf: self.buf,
+            filled: &mut self.filled,
+            init: &mut self.init,
+        }
+    }
+
+    /// Forgets the filled part, initialized memory stays initialized.
+    pub fn clear(&mut self) -> &mut Self {
+        self.filled = 0;
+        self
+    }
+
+    /// Marks the first `n` bytes as initialized.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to make sure they actually are.
+    pub unsafe fn set_init(&mut self, n: usize) -> &mut Self {
+        self.init = self.init.max(n.min(self.capacity()));
+        self
+    }
+}
+
+/// The writable end of a [`BorrowedBuf`], only ever appends to it.
+pub struct BorrowedCursor<'a> {
+    buf: &'a mut [MaybeUninit<u8>],
+    filled: &'a mut usize,
+    init: &'a mut usize,
+    // filled length when the cursor was made, for `written`
+    start: usize,
+}
+
+impl fmt::Debug for BorrowedCursor<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BorrowedCursor")
+            .field("written", &self.written())
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+
+impl<'a> BorrowedCursor<'a> {
+    pub fn reborrow(&mut self) -> BorrowedCursor<'_> {
+        BorrowedCursor {
+            start: *self.filled,
+            buf: self.buf,
+            filled: self.filled,
+            init: self.init,
+        }
+    }
+
+    /// Space left to fill.
+    pub fn capacity(&self) -> usize {
+        self.buf.len() - *self.filled
+    }
+
+    /// Bytes filled through this cursor and its reborrows.
+    pub fn written(&self) -> usize {
+        *self.filled - self.start
+    }
+
+    /// The unfilled part that is already initialized.
+    pub fn init_mut(&mut self) -> &mut [u8] {
+        let init = &mut self.buf[*self.filled..*self.init];
+        unsafe { &mut *(init as *mut [MaybeUninit<u8>] as *mut [u8]) }
+    }
+
+    /// Zeroes whatever is still uninitialized and returns the whole unfilled part.
+    pub fn ensure_init(&mut self) -> &mut [u8] {
+        self.buf[*self.init..].fill(MaybeUninit::new(0));
+        *self.init = self.buf.len();
+        self.init_mut()
+    }
+
+    /// The whole unfilled part, initialized or not.
+    ///
+    /// # Safety
+    ///
+    /// The caller must not write uninitialized bytes into the initialized part.
+    pub unsafe fn as_mut(&mut self) -> &mut [MaybeUninit<u8>] {
+        &mut self.buf[*self.filled..]
+    }
+
+    /// Marks `n` more bytes as filled.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to make sure they were written.
+    pub unsafe fn advance(&mut self, n: usize) -> &mut Self {
+        assert!(n <= self.capacity(), "advanced past the end of the buffer");
+        *self.filled += n;
+        *self.init = (*self.init).max(*self.filled);
+        self
+    }
+
+    /// Marks the first `n` unfilled bytes as initialized.
+    ///
+    /// # Safety
+    ///
+    /// The caller has to make sure they actually are.
+    pub unsafe fn set_init(&mut self, n: usize) -> &mut Self {
+        *self.init = (*self.init).max((*self.filled + n).min(self.buf.len()));
+        self
+    }
+
+    /// Copies `buf` in and advances past it, panics if it doesn't fit.
+    pub fn append(&mut self, buf: &[u8]) {
+        assert!(buf.len() <= self.capacity(), "buffer too small to append to");
+        let filled = *self.filled;
+        for (dst, &src) in self.buf[filled..filled + buf.len()].iter_mut().zip(buf) {
+            *dst = MaybeUninit::new(src);
+        }
+        unsafe { self.advance(buf.len()); }
+    }
+}
@@ -0,0 +1,334 @@
+use core::{fmt, mem::ManuallyDrop, ptr};
+
+use alloc::{boxed::Box, vec, vec::Vec};
+
+use crate::io::{self, BufRead, Error, ErrorKind, Read, Seek, SeekFrom, Write};
+
+pub(crate) const DEFAULT_BUF_SIZE: usize = 8 * 1024;
+
+pub struct BufReader<R: ?Sized> {
+    buf: Box<[u8]>,
+    pos: usize,
+    filled: usize,
+    inner: R,
+}
+
+impl<R: Read> BufReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    pub fn with_capacity(capacity: usize, inner: R) -> Self {
+        Self { buf: vec![0; capacity].into_boxed_slice(), pos: 0, filled: 0, inner }
+    }
+}
+
+impl<R: ?Sized> BufReader<R> {
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..self.filled]
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn into_inner(self) -> R where R: Sized {
+        self.inner
+    }
+
+    fn discard_buffer(&mut self) {
+        self.pos = 0;
+        self.filled = 0;
+    }
+}
+
+impl<R: ?Sized + Read> Read for BufReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        // nothing buffered and the caller's buffer is bigger than ours, skip the extra copy
+        if self.pos == self.filled && buf.len() >= self.capacity() {
+            self.discard_buffer();
+            return self.inner.read(buf);
+        }
+        let available = self.fill_buf()?;
+        let n = available.len().min(buf.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl<R: ?Sized + Read> BufRead for BufReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos >= self.filled {
+            self.filled = self.inner.read(&mut self.buf)?;
+            self.pos = 0;
+        }
+        Ok(self.buffer())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.filled);
+    }
+}
+
+impl<R: ?Sized + Seek> Seek for BufReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let result = if let SeekFrom::Current(n) = pos {
+            // the inner cursor is ahead of ours by whatever is still buffered
+            let remainder = (self.filled - self.pos) as i64;
+            let offset = n.checked_sub(remainder).ok_or(Error::const_new(ErrorKind::InvalidInput, "invalid seek to a negative or overflowing position"))?;
+            self.inner.seek(SeekFrom::Current(offset))?
+        } else {
+            self.inner.seek(pos)?
+        };
+        self.discard_buffer();
+        Ok(result)
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        let remainder = (self.filled - self.pos) as u64;
+        self.inner.stream_position().map(|pos| {
+            pos.checked_sub(remainder).expect("overflow when subtracting remaining buffer size from inner stream position")
+        })
+    }
+
+    fn seek_relative(&mut self, offset: i64) -> io::Result<()> {
+        match (self.pos as i64).checked_add(offset) {
+            Some(pos) if pos >= 0 && pos as usize <= self.filled => {
+                self.pos = pos as usize;
+                Ok(())
+            },
+            _ => self.seek(SeekFrom::Current(offset)).map(drop),
+        }
+    }
+}
+
+impl<R: ?Sized + fmt::Debug> fmt::Debug for BufReader<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufReader")
+            .field("reader", &&self.inner)
+            .field("buffer", &format_args!("{}/{}", self.filled - self.pos, self.capacity()))
+            .finish()
+    }
+}
+
+pub struct BufWriter<W: ?Sized + Write> {
+    buf: Vec<u8>,
+    // set while the inner writer is called directly, a panic in there must not make drop write again
+    panicked: bool,
+    inner: W,
+}
+
+impl<W: Write> BufWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self::with_capacity(DEFAULT_BUF_SIZE, inner)
+    }
+
+    pub fn with_capacity(capacity: usize, inner: W) -> Self {
+        Self { buf: Vec::with_capacity(capacity), panicked: false, inner }
+    }
+
+    pub fn into_inner(mut self) -> Result<W, IntoInnerError<BufWriter<W>>> {
+        match self.flush_buf() {
+            Err(e) => Err(IntoInnerError(self, e)),
+            Ok(()) => {
+                let this = ManuallyDrop::new(self);
+                // SAFETY: `this` is never used or dropped again
+                let (buf, inner) = unsafe { (ptr::read(&this.buf), ptr::read(&this.inner)) };
+                drop(buf);
+                Ok(inner)
+            },
+        }
+    }
+}
+
+impl<W: ?Sized + Write> BufWriter<W> {
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    pub(crate) fn flush_buf(&mut self) -> io::Result<()> {
+        let mut written = 0;
+        let mut ret = Ok(());
+        while written < self.buf.len() {
+            self.panicked = true;
+            let r = self.inner.write(&self.buf[written..]);
+            self.panicked = false;
+
+            match r {
+                Ok(0) => {
+                    ret = Err(Error::const_new(ErrorKind::WriteZero, "failed to write the buffered data"));
+                    break;
+                },
+                Ok(n) => written += n,
+                Err(e) => {
+                    ret = Err(e);
+                    break;
+                },
+            }
+        }
+        self.buf.drain(..written);
+        ret
+    }
+
+    // buffers as much of `buf` as fits without flushing
+    pub(crate) fn write_to_buf(&mut self, buf: &[u8]) -> usize {
+        let n = buf.len().min(self.capacity() - self.buf.len());
+        self.buf.extend_from_slice(&buf[..n]);
+        n
+    }
+}
+
+impl<W: ?Sized + Write> Write for BufWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.buf.len() + buf.len() > self.capacity() {
+            self.flush_buf()?;
+        }
+        if buf.len() >= self.capacity() {
+            self.panicked = true;
+            let r = self.inner.write(buf);
+            self.panicked = false;
+            r
+        } else {
+            self.buf.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.flush_buf()?;
+        self.inner.flush()
+    }
+}
+
+impl<W: ?Sized + Write + Seek> Seek for BufWriter<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.flush_buf()?;
+        self.inner.seek(pos)
+    }
+}
+
+impl<W: ?Sized + Write> Drop for BufWriter<W> {
+    fn drop(&mut self) {
+        if !self.panicked {
+            // errors can't be reported from drop, call flush first to see them
+            let _ = self.flush_buf();
+        }
+    }
+}
+
+impl<W: ?Sized + Write + fmt::Debug> fmt::Debug for BufWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BufWriter")
+            .field("writer", &&self.inner)
+            .field("buffer", &format_args!("{}/{}", self.buf.len(), self.capacity()))
+            .finish()
+    }
+}
+
+/// Error from [`BufWriter::into_inner`], holding the writer whose buffer couldn't be flushed.
+#[derive(Debug)]
+pub struct IntoInnerError<W>(W, Error);
+
+impl<W> IntoInnerError<W> {
+    pub fn error(&self) -> &Error {
+        &self.1
+    }
+
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+
+    pub fn into_error(self) -> Error {
+        self.1
+    }
+
+    pub fn into_parts(self) -> (Error, W) {
+        (self.1, self.0)
+    }
+}
+
+impl<W> fmt::Display for IntoInnerError<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.1.fmt(f)
+    }
+}
+
+pub struct LineWriter<W: ?Sized + Write> {
+    inner: BufWriter<W>,
+}
+
+impl<W: Write> LineWriter<W> {
+    pub fn new(inner: W) -> Self {
+        // lines are rarely longer than this
+        Self::with_capacity(1024, inner)
+    }
+
+    pub fn with_capacity(capacity: usize, inner: W) -> Self {
+        Self { inner: BufWriter::with_capacity(capacity, inner) }
+    }
+
+    pub fn into_inner(self) -> Result<W, IntoInnerError<LineWriter<W>>> {
+        self.inner.into_inner().map_err(|IntoInnerError(inner, e)| IntoInnerError(LineWriter { inner }, e))
+    }
+}
+
+impl<W: ?Sized + Write> LineWriter<W> {
+    pub fn get_ref(&self) -> &W {
+        self.inner.get_ref()
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.get_mut()
+    }
+}
+
+impl<W: ?Sized + Write> Write for LineWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let Some(last_nl) = buf.iter().rposition(|&b| b == b'\n') else {
+            // a finished line still sitting in the buffer goes out before anything else is added
+            if self.inner.buffer().last() == Some(&b'\n') {
+                self.inner.flush_buf()?;
+            }
+            return self.inner.write(buf);
+        };
+
+        self.inner.flush_buf()?;
+        let lines = &buf[..=last_nl];
+        let flushed = self.inner.get_mut().write(lines)?;
+        if flushed < lines.len() {
+            return Ok(flushed);
+        }
+        Ok(flushed + self.inner.write_to_buf(&buf[flushed..]))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: ?Sized + Write + fmt::Debug> fmt::Debug for LineWriter<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LineWriter").field("writer", &self.get_ref()).finish()
+    }
+}
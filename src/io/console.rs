@@ -0,0 +1,100 @@
+use core::fmt;
+
+use crate::io::ffi_printl;
+
+/// Console sink that formats into a fixed buffer on the stack, so it works
+/// without the allocator (the panic handler relies on this).
+///
+/// Every `\n` ends one `printl` call, longer lines are split into several calls
+/// and NUL bytes come out as `\0`. Whatever is left without a newline is
+/// printed when the writer is dropped.
+pub struct ConsoleWriter<const N: usize = 256> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> ConsoleWriter<N> {
+    pub const fn new() -> Self {
+        // room for a whole UTF-8 sequence plus the terminator
+        const { assert!(N >= 8, "ConsoleWriter buffer is too small") };
+        Self { buf: [0; N], len: 0 }
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            match b {
+                b'\n' => self.end_line(),
+                0 => {
+                    self.push(b'\\');
+                    self.push(b'0');
+                },
+                b => self.push(b),
+            }
+        }
+    }
+
+    /// Prints whatever is pending, same as dropping the writer.
+    pub fn finish(self) {}
+
+    fn push(&mut self, b: u8) {
+        if self.len == N - 1 {
+            self.split_line();
+        }
+        self.buf[self.len] = b;
+        self.len += 1;
+    }
+
+    fn end_line(&mut self) {
+        self.buf[self.len] = 0;
+        unsafe { ffi_printl(self.buf.as_ptr() as _); }
+        self.len = 0;
+    }
+
+    // the buffer is full in the middle of a line, print it but keep a trailing
+    // unfinished UTF-8 sequence for the next piece
+    fn split_line(&mut self) {
+        let tail = self.len.saturating_sub(4);
+        let cut = match self.buf[tail..self.len].iter().rposition(|&b| b & 0xc0 != 0x80) {
+            Some(pos) if tail + pos + utf8_width(self.buf[tail + pos]) > self.len => tail + pos,
+            _ => self.len,
+        };
+
+        let mut rest = [0u8; 4];
+        let rest_len = self.len - cut;
+        rest[..rest_len].copy_from_slice(&self.buf[cut..self.len]);
+        self.len = cut;
+        self.end_line();
+        self.buf[..rest_len].copy_from_slice(&rest[..rest_len]);
+        self.len = rest_len;
+    }
+}
+
+fn utf8_width(lead: u8) -> usize {
+    match lead {
+        0xf0.. => 4,
+        0xe0.. => 3,
+        0xc0.. => 2,
+        _ => 1,
+    }
+}
+
+impl<const N: usize> Default for ConsoleWriter<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Write for ConsoleWriter<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl<const N: usize> Drop for ConsoleWriter<N> {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            self.end_line();
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::io::{self, BufRead, Error, ErrorKind, Read, Seek, SeekFrom, Write};
+
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct Cursor<T> {
+    inner: T,
+    pos: u64,
+}
+
+impl<T> Cursor<T> {
+    pub const fn new(inner: T) -> Self {
+        Self { inner, pos: 0 }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub const fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    pub const fn position(&self) -> u64 {
+        self.pos
+    }
+
+    pub fn set_position(&mut self, pos: u64) {
+        self.pos = pos;
+    }
+}
+
+impl<T: AsRef<[u8]>> Cursor<T> {
+    fn remaining(&self) -> &[u8] {
+        let inner = self.inner.as_ref();
+        &inner[(self.pos.min(inner.len() as u64) as usize)..]
+    }
+}
+
+impl<T: AsRef<[u8]>> Seek for Cursor<T> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                self.pos = n;
+                return Ok(n);
+            },
+            SeekFrom::End(n) => (self.inner.as_ref().len() as u64, n),
+            SeekFrom::Current(n) => (self.pos, n),
+        };
+        match base.checked_add_signed(offset) {
+            Some(n) => {
+                self.pos = n;
+                Ok(n)
+            },
+            None => Err(Error::const_new(ErrorKind::InvalidInput, "invalid seek to a negative or overflowing position")),
+        }
+    }
+
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+impl<T: AsRef<[u8]>> Read for Cursor<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.remaining().read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    // fails without reading anything when fewer than `buf.len()` bytes are
+    // left, the position stays where it was
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.remaining().read_exact(buf)?;
+        self.pos += buf.len() as u64;
+        Ok(())
+    }
+}
+
+impl<T: AsRef<[u8]>> BufRead for Cursor<T> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.remaining())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt as u64;
+    }
+}
+
+fn slice_write(pos_mut: &mut u64, slice: &mut [u8], buf: &[u8]) -> io::Result<usize> {
+    let pos = (*pos_mut).min(slice.len() as u64) as usize;
+    let n = (&mut slice[pos..]).write(buf)?;
+    *pos_mut += n as u64;
+    Ok(n)
+}
+
+fn vec_write(pos_mut: &mut u64, vec: &mut Vec<u8>, buf: &[u8]) -> io::Result<usize> {
+    let pos = usize::try_from(*pos_mut)
+        .map_err(|_| Error::const_new(ErrorKind::InvalidInput, "cursor position exceeds maximum possible vector length"))?;
+    // writing past the end fills the gap with zeros
+    if vec.len() < pos {
+        vec.resize(pos, 0);
+    }
+    let overlap = (vec.len() - pos).min(buf.len());
+    vec[pos..pos + overlap].copy_from_slice(&buf[..overlap]);
+    vec.extend_from_slice(&buf[overlap..]);
+    *pos_mut = (pos + buf.len()) as u64;
+    Ok(buf.len())
+}
+
+impl Write for Cursor<&mut [u8]> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        slice_write(&mut self.pos, self.inner, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<const N: usize> Write for Cursor<[u8; N]> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        slice_write(&mut self.pos, &mut self.inner, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Write for Cursor<Box<[u8]>> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        slice_write(&mut self.pos, &mut self.inner, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Write for Cursor<&mut Vec<u8>> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        vec_write(&mut self.pos, self.inner, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Write for Cursor<Vec<u8>> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        vec_write(&mut self.pos, &mut self.inner, buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+use core::fmt::{self, Display};
+
+use alloc::{boxed::Box, ffi::NulError, string::{String, ToString}};
+
+use crate::fs::FsResult;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    NotFound,
+    PermissionDenied,
+    ConnectionRefused,
+    ConnectionReset,
+    HostUnreachable,
+    NetworkUnreachable,
+    ConnectionAborted,
+    NotConnected,
+    AddrInUse,
+    AddrNotAvailable,
+    NetworkDown,
+    BrokenPipe,
+    AlreadyExists,
+    WouldBlock,
+    NotADirectory,
+    IsADirectory,
+    DirectoryNotEmpty,
+    ReadOnlyFilesystem,
+    InvalidInput,
+    InvalidData,
+    TimedOut,
+    WriteZero,
+    StorageFull,
+    NotSeekable,
+    QuotaExceeded,
+    FileTooLarge,
+    ResourceBusy,
+    ExecutableFileBusy,
+    Deadlock,
+    CrossesDevices,
+    TooManyLinks,
+    InvalidFilename,
+    ArgumentListTooLong,
+    Interrupted,
+    Unsupported,
+    UnexpectedEof,
+    OutOfMemory,
+    InProgress,
+    Other,
+}
+
+impl Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let str = match self {
+            Self::NotFound => "not found",
+            Self::PermissionDenied => "permission denied",
+            Self::ConnectionRefused => "connection refused",
+            Self::ConnectionReset => "connection reset",
+            Self::HostUnreachable => "host unreachable",
+            Self::NetworkUnreachable => "network unreachable",
+            Self::ConnectionAborted => "connection aborted",
+            Self::NotConnected => "not connected",
+            Self::AddrInUse => "address in use",
+            Self::AddrNotAvailable => "address not available",
+            Self::NetworkDown => "network down",
+            Self::BrokenPipe => "broken pipe",
+            Self::AlreadyExists => "entity already exists",
+            Self::WouldBlock => "operation would block",
+            Self::IsADirectory => "is a directory",
+            Self::NotADirectory => "not a directory",
+            Self::DirectoryNotEmpty => "directory not empty",
+            Self::ReadOnlyFilesystem => "readonly filesystem",
+            Self::InvalidInput => "invalid input parameter",
+            Self::InvalidData => "invalid data",
+            Self::TimedOut => "timed out",
+            Self::WriteZero => "write zero",
+            Self::StorageFull => "no storage space",
+            Self::NotSeekable => "seek on unseekable file",
+            Self::QuotaExceeded => "quota exceeded",
+            Self::FileTooLarge => "file too large",
+            Self::ResourceBusy => "resource busy",
+            Self::ExecutableFileBusy => "executable file busy",
+            Self::Deadlock => "deadlock",
+            Self::CrossesDevices => "cross-device link or rename",
+            Self::TooManyLinks => "too many links",
+            Self::InvalidFilename => "invalid filename",
+            Self::ArgumentListTooLong => "argument list too long",
+            Self::Interrupted => "operation interrupted",
+            Self::Unsupported => "unsupported",
+            Self::UnexpectedEof => "unexpected end of file",
+            Self::OutOfMemory => "out of memory",
+            Self::InProgress => "in progress",
+            Self::Other => "other error",
+        };
+        f.write_str(str)
+    }
+}
+
+#[derive(Debug)]
+enum ErrorData {
+    Static(&'static str),
+    Error(Box<dyn core::error::Error + Sync + Send>),
+    // result code as returned by RedactedOS
+    Os(i32),
+}
+
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    err: Option<ErrorData>,
+}
+
+impl Error {
+    pub(crate) const READ_EXACT_EOF: Self = Self::const_new(ErrorKind::UnexpectedEof, "failed to fill whole buffer");
+    pub(crate) const WRITE_ALL_EOF: Self = Self::const_new(ErrorKind::WriteZero, "failed to write whole buffer");
+    pub(crate) const NOT_SEEKABLE: Self = Self::const_new(ErrorKind::NotSeekable, "device files can't seek");
+    pub(crate) const INVALID_UTF8: Self = Self::const_new(ErrorKind::InvalidData, "stream did not contain valid UTF-8");
+
+    pub const fn const_new(kind: ErrorKind, err: &'static str) -> Self {
+        Self { kind, err: Some(ErrorData::Static(err)) }
+    }
+
+    pub fn new(kind: ErrorKind, err: &'static str) -> Self {
+        Self { kind, err: Some(ErrorData::Static(err)) }
+    }
+
+    pub fn from_error<E: Into<Box<dyn core::error::Error + Sync + Send>>>(kind: ErrorKind, err: E) -> Self {
+        Self { kind, err: Some(ErrorData::Error(err.into())) }
+    }
+
+    pub fn os_error(kind: ErrorKind) -> Self {
+        Self { kind, err: None }
+    }
+
+    /// Creates an error from a RedactedOS filesystem result code.
+    pub fn from_raw_os_error(code: i32) -> Self {
+        Self { kind: FsResult::decode_error_kind(code), err: Some(ErrorData::Os(code)) }
+    }
+
+    pub fn other<E: Into<Box<dyn core::error::Error + Sync + Send>>>(err: E) -> Self {
+        Self { kind: ErrorKind::Other, err: Some(ErrorData::Error(err.into()))}
+    }
+
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+
+    /// The RedactedOS result code this error was created from, if any.
+    pub fn raw_os_error(&self) -> Option<i32> {
+        match self.err {
+            Some(ErrorData::Os(code)) => Some(code),
+            _ => None,
+        }
+    }
+
+    pub fn get_ref(&self) -> Option<&(dyn core::error::Error + Sync + Send + 'static)> {
+        match self.err {
+            Some(ErrorData::Error(ref err)) => Some(&**err),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self) -> Option<&mut (dyn core::error::Error + Sync + Send + 'static)> {
+        match self.err {
+            Some(ErrorData::Error(ref mut err)) => Some(&mut **err),
+            _ => None,
+        }
+    }
+
+    pub fn into_inner(self) -> Option<Box<dyn core::error::Error + Sync + Send>> {
+        match self.err {
+            Some(ErrorData::Error(err)) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Takes out the wrapped error if it is an `E`, otherwise hands `self` back.
+    pub fn downcast<E: core::error::Error + Sync + Send + 'static>(self) -> core::result::Result<E, Self> {
+        match self.err {
+            Some(ErrorData::Error(err)) if err.is::<E>() => Ok(*err.downcast::<E>().unwrap()),
+            err => Err(Self { kind: self.kind, err }),
+        }
+    }
+
+    /// Wraps the error with a message saying what was being done, keeping its kind.
+    pub fn context<C: Display>(self, context: C) -> Self {
+        let kind = self.kind;
+        Self::from_error(kind, ContextError { context: context.to_string(), source: self })
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self.err {
+            Some(ErrorData::Error(ref err)) => err.source(),
+            _ => None,
+        }
+    }
+}
+
+/// What [`Error::context`] wraps an error in, the original error is its `source`.
+#[derive(Debug)]
+pub struct ContextError {
+    context: String,
+    source: Error,
+}
+
+impl ContextError {
+    pub fn context(&self) -> &str {
+        &self.context
+    }
+}
+
+impl Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.context, self.source)
+    }
+}
+
+impl core::error::Error for ContextError {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+/// Attaches context to the error of an [`io::Result`](Result).
+///
+/// ```ignore
+/// let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+/// ```
+pub trait Context<T> {
+    fn context<C: Display>(self, context: C) -> Result<T>;
+    fn with_context<C: Display, F: FnOnce() -> C>(self, f: F) -> Result<T>;
+}
+
+impl<T> Context<T> for Result<T> {
+    fn context<C: Display>(self, context: C) -> Result<T> {
+        self.map_err(|err| err.context(context))
+    }
+
+    fn with_context<C: Display, F: FnOnce() -> C>(self, f: F) -> Result<T> {
+        self.map_err(|err| err.context(f()))
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.err {
+            Some(ref err) => match err {
+                ErrorData::Static(str) if self.kind == ErrorKind::Other => str.fmt(f),
+                ErrorData::Static(str) => write!(f, "{}: {}", self.kind, str),
+                ErrorData::Error(err) => err.fmt(f),
+                ErrorData::Os(code) => write!(f, "{} (os error {})", FsResult::error_string(*code), code),
+            },
+            None => self.kind.fmt(f),
+        }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(value: ErrorKind) -> Self {
+        Self { kind: value, err: None }
+    }
+}
+
+impl From<NulError> for Error {
+    fn from(value: NulError) -> Self {
+        Self::from_error(ErrorKind::InvalidInput, value)
+    }
+}
+
+impl From<FsResult> for Error {
+    fn from(value: FsResult) -> Self {
+        Self::from_raw_os_error(value as i32)
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,107 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::io::{self, BorrowedCursor, BufRead, Error, IoSliceMut, Read, Write};
+
+impl Read for &[u8] {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.len());
+        let (a, b) = self.split_at(n);
+        buf[..n].copy_from_slice(a);
+        *self = b;
+        Ok(n)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let mut read = 0;
+        for buf in bufs {
+            read += self.read(buf)?;
+            if self.is_empty() {
+                break;
+            }
+        }
+        Ok(read)
+    }
+
+    fn read_buf(&mut self, mut cursor: BorrowedCursor<'_>) -> io::Result<()> {
+        let n = cursor.capacity().min(self.len());
+        let (a, b) = self.split_at(n);
+        cursor.append(a);
+        *self = b;
+        Ok(())
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        if buf.len() > self.len() {
+            // std leaves the slice exhausted when it can't fill the buffer
+            *self = &self[self.len()..];
+            return Err(Error::READ_EXACT_EOF);
+        }
+        let (a, b) = self.split_at(buf.len());
+        buf.copy_from_slice(a);
+        *self = b;
+        Ok(())
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> io::Result<usize> {
+        let n = self.len();
+        buf.extend_from_slice(self);
+        *self = &self[n..];
+        Ok(n)
+    }
+
+    fn read_to_string(&mut self, buf: &mut String) -> io::Result<usize> {
+        let s = str::from_utf8(self).map_err(|_| Error::INVALID_UTF8)?;
+        buf.push_str(s);
+        let n = self.len();
+        *self = &self[n..];
+        Ok(n)
+    }
+}
+
+impl BufRead for &[u8] {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(*self)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        *self = &self[amt.min(self.len())..];
+    }
+}
+
+impl Write for &mut [u8] {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let n = data.len().min(self.len());
+        let (a, b) = core::mem::take(self).split_at_mut(n);
+        a.copy_from_slice(&data[..n]);
+        *self = b;
+        Ok(n)
+    }
+
+    fn write_all(&mut self, data: &[u8]) -> io::Result<()> {
+        if self.write(data)? == data.len() {
+            Ok(())
+        } else {
+            Err(Error::WRITE_ALL_EOF)
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Write for Vec<u8> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.extend_from_slice(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -1,63 +1,137 @@
 use crate::io::input::keycodes::{Key, ModifierKey};
 
 #[derive(Clone, Copy, Default, Debug)]
-#[repr(C)]
 pub struct Keypress {
     pub modifier: ModifierKey,
-    rsvd: u8,
     pub keys: [Key; 6],
 }
 
-#[derive(Clone, Copy, Default, Debug)]
-#[repr(C)]
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
 pub enum KeyEventType {
     #[default]
     KeyRelease,
     KeyPress,
     ModRelease,
     ModPress,
+    Unknown(u32),
 }
 
 #[derive(Clone, Copy, Default, Debug)]
-#[repr(C)]
 pub struct KeyEvent {
     pub ty: KeyEventType,
     pub key: Key,
     pub modifier: ModifierKey,
 }
 
+// what the C side actually writes, the enums only get built from these
+// after checking so a value without a variant can't end up in them
+
+#[doc(hidden)]
+#[derive(Clone, Copy, Default, Debug)]
+#[repr(C)]
+pub struct RawKeypress {
+    pub modifier: u8,
+    pub rsvd: u8,
+    pub keys: [u8; 6],
+}
+
+#[doc(hidden)]
+#[derive(Clone, Copy, Default, Debug)]
+#[repr(C)]
+pub struct RawKeyEvent {
+    pub ty: u32,
+    pub key: u8,
+    pub modifier: u8,
+}
+
+impl From<u32> for KeyEventType {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => Self::KeyRelease,
+            1 => Self::KeyPress,
+            2 => Self::ModRelease,
+            3 => Self::ModPress,
+            other => Self::Unknown(other),
+        }
+    }
+}
+
+impl From<KeyEventType> for u32 {
+    fn from(value: KeyEventType) -> Self {
+        match value {
+            KeyEventType::KeyRelease => 0,
+            KeyEventType::KeyPress => 1,
+            KeyEventType::ModRelease => 2,
+            KeyEventType::ModPress => 3,
+            KeyEventType::Unknown(other) => other,
+        }
+    }
+}
+
+impl From<RawKeypress> for Keypress {
+    fn from(raw: RawKeypress) -> Self {
+        Self { modifier: raw.modifier.into(), keys: raw.keys.map(Key::from_hid) }
+    }
+}
+
+impl From<Keypress> for RawKeypress {
+    fn from(keypress: Keypress) -> Self {
+        Self { modifier: keypress.modifier.into(), rsvd: 0, keys: keypress.keys.map(u8::from) }
+    }
+}
+
+impl From<RawKeyEvent> for KeyEvent {
+    fn from(raw: RawKeyEvent) -> Self {
+        Self { ty: raw.ty.into(), key: Key::from_hid(raw.key), modifier: raw.modifier.into() }
+    }
+}
+
+impl From<KeyEvent> for RawKeyEvent {
+    fn from(event: KeyEvent) -> Self {
+        Self { ty: event.ty.into(), key: event.key.into(), modifier: event.modifier.into() }
+    }
+}
+
+#[cfg(not(feature = "mock"))]
 unsafe extern "C" {
     #[link_name = "read_key"]
-    pub unsafe fn ffi_read_key(kp: *mut Keypress) -> bool;
+    pub unsafe fn ffi_read_key(kp: *mut RawKeypress) -> bool;
     #[link_name = "read_event"]
-    pub unsafe fn ffi_read_event(event: *mut KeyEvent) -> bool;
+    pub unsafe fn ffi_read_event(event: *mut RawKeyEvent) -> bool;
     #[link_name = "hid_to_char"]
     pub unsafe fn ffi_hid_to_char(hid: u8) -> u8;
 }
 
+#[cfg(feature = "mock")]
+pub use crate::mock::keyboard::{ffi_read_key, ffi_read_event, ffi_hid_to_char};
+
 pub fn hid_to_char(hid: u8) -> char {
+    try_hid_to_char(hid).unwrap_or('?')
+}
+
+pub fn try_hid_to_char(hid: u8) -> Option<char> {
     let c = unsafe { ffi_hid_to_char(hid) };
     if c == 0 {
-        '?'
+        None
     } else {
-        c as char
+        Some(c as char)
     }
 }
 
 pub fn read_key() -> Option<Keypress> {
-    let mut keypress = Keypress::default();
+    let mut keypress = RawKeypress::default();
     if unsafe { ffi_read_key(&mut keypress) } {
-        Some(keypress)
+        Some(keypress.into())
     } else {
         None
     }
 }
 
 pub fn read_event() -> Option<KeyEvent> {
-    let mut event = KeyEvent::default();
+    let mut event = RawKeyEvent::default();
     if unsafe { ffi_read_event(&mut event) } {
-        Some(event)
+        Some(event.into())
     } else {
         None
     }
-}
\ No newline at end of file
+}
@@ -1,6 +1,6 @@
 use bitmask_enum::bitmask;
 
-use crate::io::input::keyboard::hid_to_char;
+use crate::io::input::keyboard::{hid_to_char, try_hid_to_char};
 
 #[bitmask(u8)]
 #[derive(Default)]
@@ -15,10 +15,38 @@ pub enum ModifierKey {
     RMETA,
 }
 
-#[repr(u8)]
-#[allow(nonstandard_style)]
-#[derive(Clone, Copy, Default, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-pub enum Key {
+// the variants are listed once and expanded into the enum and both directions
+// of the hid code mapping
+macro_rules! keys {
+    ($($(#[$attr:meta])* $name:ident = $hid:literal,)*) => {
+        #[repr(u8)]
+        #[allow(nonstandard_style)]
+        #[derive(Clone, Copy, Default, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub enum Key {
+            $($(#[$attr])* $name = $hid,)*
+            // any code without a variant above, e.g. the reserved ones
+            Unknown(u8),
+        }
+
+        impl Key {
+            pub const fn hid(&self) -> u8 {
+                match self {
+                    $(Self::$name => $hid,)*
+                    Self::Unknown(hid) => *hid,
+                }
+            }
+
+            const fn known(hid: u8) -> Option<Self> {
+                match hid {
+                    $($hid => Some(Self::$name),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+keys! {
     #[default]
     KEY_NONE = 0x00 , // No key pressed
     KEY_ERR_OVF = 0x01 , //  Keyboard Error Roll Over - used for all slots if too many keys are pressed ("Phantom key")
@@ -272,7 +300,33 @@ pub enum Key {
 }
 
 impl Key {
+    /// The key for a hid code, [`Key::Unknown`] if there is no variant for it.
+    pub const fn from_hid(hid: u8) -> Self {
+        match Self::known(hid) {
+            Some(key) => key,
+            None => Self::Unknown(hid),
+        }
+    }
+
     pub fn as_char(&self) -> char {
-        hid_to_char(*self as u8)
+        hid_to_char(self.hid())
+    }
+
+    pub fn to_char(&self) -> Option<char> {
+        try_hid_to_char(self.hid())
     }
-}
\ No newline at end of file
+}
+
+impl From<Key> for u8 {
+    fn from(key: Key) -> Self {
+        key.hid()
+    }
+}
+
+impl TryFrom<u8> for Key {
+    type Error = u8;
+
+    fn try_from(hid: u8) -> Result<Self, u8> {
+        Self::known(hid).ok_or(hid)
+    }
+}
@@ -0,0 +1,57 @@
+use core::{fmt, ops::{Deref, DerefMut}};
+
+/// One of the buffers [`Read::read_vectored`](crate::io::Read::read_vectored) scatters into.
+#[repr(transparent)]
+pub struct IoSliceMut<'a>(&'a mut [u8]);
+
+impl<'a> IoSliceMut<'a> {
+    pub fn new(buf: &'a mut [u8]) -> Self {
+        Self(buf)
+    }
+
+    /// Drops the first `n` bytes, panics if there are fewer.
+    pub fn advance(&mut self, n: usize) {
+        let buf = core::mem::take(&mut self.0);
+        self.0 = &mut buf[n..];
+    }
+
+    /// Skips `n` bytes over a list of slices, removing the ones used up entirely.
+    pub fn advance_slices(bufs: &mut &mut [IoSliceMut<'a>], n: usize) {
+        let mut left = n;
+        let mut remove = 0;
+        for buf in bufs.iter() {
+            if buf.len() > left {
+                break;
+            }
+            left -= buf.len();
+            remove += 1;
+        }
+
+        *bufs = &mut core::mem::take(bufs)[remove..];
+        if bufs.is_empty() {
+            assert!(left == 0, "advancing io slices beyond their length");
+        } else {
+            bufs[0].advance(left);
+        }
+    }
+}
+
+impl Deref for IoSliceMut<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.0
+    }
+}
+
+impl DerefMut for IoSliceMut<'_> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.0
+    }
+}
+
+impl fmt::Debug for IoSliceMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
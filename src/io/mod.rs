@@ -1,28 +1,51 @@
-use core::{ffi::c_char, fmt::{self, Display, Write}};
+#[cfg(not(feature = "mock"))]
+use core::ffi::c_char;
+use core::fmt::{self, Write as _};
 
-use alloc::{boxed::Box, ffi::{CString, NulError}, string::String, vec::Vec};
+use alloc::{boxed::Box, string::String, vec::Vec};
 
 pub mod input;
-
+mod error;
+pub use error::*;
+mod console;
+pub use console::*;
+mod stdio;
+pub use stdio::*;
+mod stdin;
+pub use stdin::*;
+mod buffered;
+pub use buffered::*;
+mod cursor;
+pub use cursor::*;
+mod borrowed_buf;
+pub use borrowed_buf::*;
+mod io_slice;
+pub use io_slice::*;
+mod impls;
+mod util;
+pub use util::*;
+
+#[cfg(not(feature = "mock"))]
 unsafe extern "C" {
     #[link_name = "printl"]
     pub unsafe fn ffi_printl(str: *const c_char);
 }
 
+#[cfg(feature = "mock")]
+pub use crate::mock::console::ffi_printl;
+
 #[doc(hidden)]
 pub fn puts(str: &str) {
-    let cstr = CString::new(str).unwrap();
-    unsafe {
-        ffi_printl(cstr.as_ptr());
-    }
+    let mut out = ConsoleWriter::<256>::new();
+    out.write_bytes(str.as_bytes());
+    out.write_bytes(b"\n");
 }
 
 #[doc(hidden)]
 pub fn putfmt(fmt: fmt::Arguments) -> fmt::Result {
-    let mut s = String::new();
-    s.write_fmt(fmt).unwrap();
-    let cstr = CString::new(s).unwrap();
-    unsafe { ffi_printl(cstr.as_ptr()); }
+    let mut out = ConsoleWriter::<256>::new();
+    out.write_fmt(fmt)?;
+    out.write_bytes(b"\n");
     Ok(())
 }
 
@@ -40,135 +63,355 @@ pub enum SeekFrom {
     Current(i64),
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-#[non_exhaustive]
-pub enum ErrorKind {
-    NotFound,
-    NotADirectory,
-    IsADirectory,
-    ReadOnlyFilesystem,
-    InvalidData,
-    UnexpectedEof,
-    Other,
-}
-
-impl Display for ErrorKind {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let str = match self {
-            Self::NotFound => "not found",
-            Self::IsADirectory => "is a directory",
-            Self::NotADirectory => "not a directory",
-            Self::ReadOnlyFilesystem => "readonly filesystem",
-            Self::InvalidData => "invalid data",
-            Self::UnexpectedEof => "unexpected end of file",
-            Self::Other => "other error",
-        };
-        f.write_str(str)
+// reads straight into the spare capacity, so readers overriding `read_buf` never
+// see zeroed memory and the rest only zero each byte once
+pub(crate) fn default_read_to_end<R: Read + ?Sized>(r: &mut R, buf: &mut Vec<u8>) -> Result<usize> {
+    let start_len = buf.len();
+    let mut max_read = buffered::DEFAULT_BUF_SIZE;
+    let mut init = 0;
+    loop {
+        if buf.len() == buf.capacity() {
+            buf.reserve(32);
+        }
+
+        let spare = buf.spare_capacity_mut();
+        let spare_len = spare.len().min(max_read);
+        let mut read_buf = BorrowedBuf::from(&mut spare[..spare_len]);
+        unsafe { read_buf.set_init(init); }
+        r.read_buf(read_buf.unfilled())?;
+
+        let read = read_buf.len();
+        if read == 0 {
+            return Ok(buf.len() - start_len);
+        }
+        init = read_buf.init_len() - read;
+        unsafe { buf.set_len(buf.len() + read); }
+
+        if read == spare_len && spare_len == max_read {
+            max_read = max_read.saturating_mul(2);
+        }
     }
 }
 
-#[derive(Debug)]
-enum ErrorData {
-    Static(&'static str),
-    Error(Box<dyn core::error::Error + Sync + Send>),
+// what `read_vectored` does for readers that can't scatter
+pub(crate) fn default_read_vectored<F: FnOnce(&mut [u8]) -> Result<usize>>(read: F, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+    let buf = bufs.iter_mut().find(|buf| !buf.is_empty()).map_or(&mut [][..], |buf| &mut **buf);
+    read(buf)
 }
 
-#[derive(Debug)]
-pub struct Error {
-    kind: ErrorKind,
-    err: Option<ErrorData>,
+pub(crate) fn default_read_buf<F: FnOnce(&mut [u8]) -> Result<usize>>(read: F, mut cursor: BorrowedCursor<'_>) -> Result<()> {
+    let n = read(cursor.ensure_init())?;
+    unsafe { cursor.advance(n); }
+    Ok(())
 }
 
-impl Error {
-    pub(crate) const READ_EXACT_EOF: Self = Self::const_new(ErrorKind::UnexpectedEof, "failed to fill whole buffer");
-    pub(crate) const INVALID_UTF8: Self = Self::const_new(ErrorKind::InvalidData, "stream did not contain valid UTF-8");
+struct Guard<'a> {
+    len: usize,
+    buf: &'a mut Vec<u8>
+}
 
-    pub const fn const_new(kind: ErrorKind, err: &'static str) -> Self {
-        Self { kind, err: Some(ErrorData::Static(err)) }
+impl Drop for Guard<'_> {
+    fn drop(&mut self) {
+        unsafe { self.buf.set_len(self.len); }
     }
+}
+
+pub trait Read {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
 
-    pub fn new(kind: ErrorKind, err: &'static str) -> Self {
-        Self { kind, err: Some(ErrorData::Static(err)) }
+    /// Reads into several buffers in order, by default only into the first non-empty one.
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        default_read_vectored(|b| self.read(b), bufs)
     }
 
-    pub fn from_error<E: Into<Box<dyn core::error::Error + Sync + Send>>>(kind: ErrorKind, err: E) -> Self {
-        Self { kind: kind, err: Some(ErrorData::Error(err.into())) }
+    /// Reads into possibly uninitialized memory, by default zeroing it and calling `read`.
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> Result<()> {
+        default_read_buf(|b| self.read(b), cursor)
     }
 
-    pub fn os_error(kind: ErrorKind) -> Self {
-        Self { kind, err: None }
+    fn read_buf_exact(&mut self, mut cursor: BorrowedCursor<'_>) -> Result<()> {
+        while cursor.capacity() > 0 {
+            let prev = cursor.written();
+            self.read_buf(cursor.reborrow())?;
+            if cursor.written() == prev {
+                return Err(Error::READ_EXACT_EOF);
+            }
+        }
+        Ok(())
     }
 
-    pub fn other<E: Into<Box<dyn core::error::Error + Sync + Send>>>(err: E) -> Self {
-        Self { kind: ErrorKind::Other, err: Some(ErrorData::Error(err.into()))}
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        default_read_to_end(self, buf)
     }
 
-    pub fn kind(&self) -> ErrorKind {
-        self.kind
+    fn read_to_string(&mut self, buf: &mut String) -> Result<usize> {
+        let mut g = Guard { len: buf.len(), buf: unsafe { buf.as_mut_vec() } };
+        let ret = self.read_to_end(g.buf);
+
+        if str::from_utf8(unsafe { g.buf.get_unchecked(g.len..) }).is_err() {
+            ret.and_then(|_| Err(Error::INVALID_UTF8))
+        } else {
+            g.len = g.buf.len();
+            ret
+        }
+    }
+
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.read(buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    buf = &mut buf[n..];
+                },
+                Err(e) => return Err(e),
+            }
+        }
+
+        if !buf.is_empty() {
+            Err(Error::READ_EXACT_EOF)
+        } else { 
+            Ok(())
+        }
+    }
+
+    fn by_ref(&mut self) -> &mut Self where Self: Sized {
+        self
+    }
+
+    fn bytes(self) -> Bytes<Self> where Self: Sized {
+        Bytes { inner: self }
+    }
+
+    fn chain<R: Read>(self, next: R) -> Chain<Self, R> where Self: Sized {
+        Chain { first: self, second: next, done_first: false }
+    }
+
+    fn take(self, limit: u64) -> Take<Self> where Self: Sized {
+        Take { inner: self, limit }
     }
 }
 
-impl Display for Error {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self.err {
-            Some(ref err) => match err {
-                ErrorData::Static(str) if self.kind == ErrorKind::Other => str.fmt(f),
-                ErrorData::Static(str) => write!(f, "{}: {}", self.kind, str),
-                ErrorData::Error(err) => err.fmt(f),
-            },
-            None => self.kind.fmt(f),
+impl<R: Read + ?Sized> Read for &mut R {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (**self).read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        (**self).read_vectored(bufs)
+    }
+
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> Result<()> {
+        (**self).read_buf(cursor)
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        (**self).read_to_end(buf)
+    }
+
+    fn read_to_string(&mut self, buf: &mut String) -> Result<usize> {
+        (**self).read_to_string(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        (**self).read_exact(buf)
+    }
+}
+
+impl<R: Read + ?Sized> Read for Box<R> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (**self).read(buf)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> Result<usize> {
+        (**self).read_vectored(bufs)
+    }
+
+    fn read_buf(&mut self, cursor: BorrowedCursor<'_>) -> Result<()> {
+        (**self).read_buf(cursor)
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
+        (**self).read_to_end(buf)
+    }
+
+    fn read_to_string(&mut self, buf: &mut String) -> Result<usize> {
+        (**self).read_to_string(buf)
+    }
+
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        (**self).read_exact(buf)
+    }
+}
+
+#[derive(Debug)]
+pub struct Bytes<R> {
+    inner: R,
+}
+
+impl<R: Read> Iterator for Bytes<R> {
+    type Item = Result<u8>;
+
+    fn next(&mut self) -> Option<Result<u8>> {
+        let mut byte = 0;
+        match self.inner.read(core::slice::from_mut(&mut byte)) {
+            Ok(0) => None,
+            Ok(_) => Some(Ok(byte)),
+            Err(e) => Some(Err(e)),
         }
     }
 }
 
-impl From<ErrorKind> for Error {
-    fn from(value: ErrorKind) -> Self {
-        Self { kind: value, err: None }
+#[derive(Debug)]
+pub struct Chain<T, U> {
+    first: T,
+    second: U,
+    done_first: bool,
+}
+
+impl<T, U> Chain<T, U> {
+    pub fn into_inner(self) -> (T, U) {
+        (self.first, self.second)
+    }
+
+    pub fn get_ref(&self) -> (&T, &U) {
+        (&self.first, &self.second)
+    }
+
+    pub fn get_mut(&mut self) -> (&mut T, &mut U) {
+        (&mut self.first, &mut self.second)
     }
 }
 
-impl From<NulError> for Error {
-    fn from(value: NulError) -> Self {
-        Self::from_error(ErrorKind::Other, value)
+impl<T: Read, U: Read> Read for Chain<T, U> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if !self.done_first {
+            match self.first.read(buf)? {
+                0 if !buf.is_empty() => self.done_first = true,
+                n => return Ok(n),
+            }
+        }
+        self.second.read(buf)
     }
 }
 
-pub type Result<T> = core::result::Result<T, Error>;
+impl<T: BufRead, U: BufRead> BufRead for Chain<T, U> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if !self.done_first {
+            match self.first.fill_buf()? {
+                [] => self.done_first = true,
+                buf => return Ok(buf),
+            }
+        }
+        self.second.fill_buf()
+    }
 
-pub(crate) fn default_read_to_end<R: Read + ?Sized>(r: &mut R, buf: &mut Vec<u8>) -> Result<usize> {
-    let start_len = buf.len();
-    loop {
-        let mut data = [0u8; 32];
-        let read = r.read(&mut data)?;
-        if read == 0 {
-            return Ok(buf.len() - start_len);
+    fn consume(&mut self, amt: usize) {
+        if !self.done_first {
+            self.first.consume(amt)
+        } else {
+            self.second.consume(amt)
         }
+    }
+}
 
-        buf.extend_from_slice(&data[..read]);
+#[derive(Debug)]
+pub struct Take<T> {
+    inner: T,
+    limit: u64,
+}
+
+impl<T> Take<T> {
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    pub fn set_limit(&mut self, limit: u64) {
+        self.limit = limit;
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
     }
 }
 
-struct Guard<'a> {
-    len: usize,
-    buf: &'a mut Vec<u8>
+impl<T: Read> Read for Take<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.limit == 0 {
+            return Ok(0);
+        }
+        let max = (buf.len() as u64).min(self.limit) as usize;
+        let n = self.inner.read(&mut buf[..max])?;
+        assert!(n as u64 <= self.limit, "number of read bytes exceeds limit");
+        self.limit -= n as u64;
+        Ok(n)
+    }
 }
 
-impl Drop for Guard<'_> {
-    fn drop(&mut self) {
-        unsafe { self.buf.set_len(self.len); }
+impl<T: BufRead> BufRead for Take<T> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        if self.limit == 0 {
+            return Ok(&[]);
+        }
+        let buf = self.inner.fill_buf()?;
+        let cap = (buf.len() as u64).min(self.limit) as usize;
+        Ok(&buf[..cap])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let amt = (amt as u64).min(self.limit) as usize;
+        self.limit -= amt as u64;
+        self.inner.consume(amt);
     }
 }
 
-pub trait Read {
-    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+fn read_until<R: BufRead + ?Sized>(r: &mut R, delim: u8, buf: &mut Vec<u8>) -> Result<usize> {
+    let mut read = 0;
+    loop {
+        let (done, used) = {
+            let available = r.fill_buf()?;
+            match available.iter().position(|&b| b == delim) {
+                Some(i) => {
+                    buf.extend_from_slice(&available[..=i]);
+                    (true, i + 1)
+                },
+                None => {
+                    buf.extend_from_slice(available);
+                    (available.is_empty(), available.len())
+                },
+            }
+        };
+        r.consume(used);
+        read += used;
+        if done {
+            return Ok(read);
+        }
+    }
+}
 
-    fn read_to_end(&mut self, buf: &mut Vec<u8>) -> Result<usize> {
-        default_read_to_end(self, buf)
+pub trait BufRead: Read {
+    fn fill_buf(&mut self) -> Result<&[u8]>;
+
+    fn consume(&mut self, amt: usize);
+
+    fn has_data_left(&mut self) -> Result<bool> {
+        self.fill_buf().map(|b| !b.is_empty())
     }
 
-    fn read_to_string(&mut self, buf: &mut String) -> Result<usize> {
+    fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize> {
+        read_until(self, byte, buf)
+    }
+
+    fn read_line(&mut self, buf: &mut String) -> Result<usize> {
         let mut g = Guard { len: buf.len(), buf: unsafe { buf.as_mut_vec() } };
-        let ret = default_read_to_end(self, g.buf);
+        let ret = read_until(self, b'\n', g.buf);
 
         if str::from_utf8(unsafe { g.buf.get_unchecked(g.len..) }).is_err() {
             ret.and_then(|_| Err(Error::INVALID_UTF8))
@@ -178,21 +421,97 @@ pub trait Read {
         }
     }
 
-    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
-        while !buf.is_empty() {
-            match self.read(buf) {
-                Ok(0) => break,
-                Ok(n) => {
-                    buf = &mut buf[n..];
-                },
-                Err(e) => return Err(e),
-            }
+    fn split(self, byte: u8) -> Split<Self> where Self: Sized {
+        Split { buf: self, delim: byte }
+    }
+
+    fn lines(self) -> Lines<Self> where Self: Sized {
+        Lines { buf: self }
+    }
+}
+
+impl<B: BufRead + ?Sized> BufRead for &mut B {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        (**self).fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
+
+    fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize> {
+        (**self).read_until(byte, buf)
+    }
+
+    fn read_line(&mut self, buf: &mut String) -> Result<usize> {
+        (**self).read_line(buf)
+    }
+}
+
+impl<B: BufRead + ?Sized> BufRead for Box<B> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        (**self).fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
+
+    fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> Result<usize> {
+        (**self).read_until(byte, buf)
+    }
+
+    fn read_line(&mut self, buf: &mut String) -> Result<usize> {
+        (**self).read_line(buf)
+    }
+}
+
+#[derive(Debug)]
+pub struct Split<B> {
+    buf: B,
+    delim: u8,
+}
+
+impl<B: BufRead> Iterator for Split<B> {
+    type Item = Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Result<Vec<u8>>> {
+        let mut buf = Vec::new();
+        match self.buf.read_until(self.delim, &mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                if buf[buf.len() - 1] == self.delim {
+                    buf.pop();
+                }
+                Some(Ok(buf))
+            },
+            Err(e) => Some(Err(e)),
         }
+    }
+}
 
-        if !buf.is_empty() {
-            Err(Error::READ_EXACT_EOF)
-        } else { 
-            Ok(())
+#[derive(Debug)]
+pub struct Lines<B> {
+    buf: B,
+}
+
+impl<B: BufRead> Iterator for Lines<B> {
+    type Item = Result<String>;
+
+    fn next(&mut self) -> Option<Result<String>> {
+        let mut buf = String::new();
+        match self.buf.read_line(&mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                if buf.ends_with('\n') {
+                    buf.pop();
+                    if buf.ends_with('\r') {
+                        buf.pop();
+                    }
+                }
+                Some(Ok(buf))
+            },
+            Err(e) => Some(Err(e)),
         }
     }
 }
@@ -213,4 +532,93 @@ pub trait Seek {
         self.seek(SeekFrom::Start(0))?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+pub trait Write {
+    fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+    fn flush(&mut self) -> Result<()>;
+
+    fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.write(buf) {
+                Ok(0) => return Err(Error::WRITE_ALL_EOF),
+                Ok(n) => buf = &buf[n..],
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> Result<()> {
+        // fmt::Error carries nothing, so the io error is stashed until formatting bails out
+        struct Adapter<'a, T: ?Sized + 'a> {
+            inner: &'a mut T,
+            error: Result<()>,
+        }
+
+        impl<T: Write + ?Sized> fmt::Write for Adapter<'_, T> {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                match self.inner.write_all(s.as_bytes()) {
+                    Ok(()) => Ok(()),
+                    Err(e) => {
+                        self.error = Err(e);
+                        Err(fmt::Error)
+                    }
+                }
+            }
+        }
+
+        let mut output = Adapter { inner: self, error: Ok(()) };
+        match fmt::write(&mut output, fmt) {
+            Ok(()) => Ok(()),
+            Err(..) => {
+                if output.error.is_err() {
+                    output.error
+                } else {
+                    Err(Error::const_new(ErrorKind::Other, "formatter error"))
+                }
+            }
+        }
+    }
+
+    fn by_ref(&mut self) -> &mut Self where Self: Sized {
+        self
+    }
+}
+
+impl<W: Write + ?Sized> Write for &mut W {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        (**self).write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        (**self).write_all(buf)
+    }
+
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> Result<()> {
+        (**self).write_fmt(fmt)
+    }
+}
+
+impl<W: Write + ?Sized> Write for Box<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        (**self).write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        (**self).flush()
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        (**self).write_all(buf)
+    }
+
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> Result<()> {
+        (**self).write_fmt(fmt)
+    }
+}
@@ -0,0 +1,232 @@
+use core::fmt;
+
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    io::{self, input::{keyboard::{self, KeyEvent, KeyEventType}, keycodes::{Key, ModifierKey}}, BufRead, Error, ErrorKind, Lines, Read, Write},
+    sync::{global, TryLockGuard},
+    thread,
+    time::Duration,
+};
+
+struct StdinState {
+    // the rest of the last line handed out by `read`
+    pending: Vec<u8>,
+    pos: usize,
+    history: Vec<String>,
+    history_enabled: bool,
+    echo: bool,
+}
+
+global! {
+    static STDIN: StdinState = StdinState {
+        pending: Vec::new(),
+        pos: 0,
+        history: Vec::new(),
+        history_enabled: false,
+        echo: true,
+    };
+}
+
+pub struct Stdin {
+    _priv: (),
+}
+
+pub struct StdinLock<'a> {
+    inner: TryLockGuard<'a, StdinState>,
+}
+
+pub fn stdin() -> Stdin {
+    Stdin { _priv: () }
+}
+
+impl Stdin {
+    /// Panics if another [`StdinLock`] is alive, there's no other thread that
+    /// could ever release it.
+    pub fn lock(&self) -> StdinLock<'static> {
+        self.try_lock().expect("stdin is already locked")
+    }
+
+    fn try_lock(&self) -> io::Result<StdinLock<'static>> {
+        const BUSY: Error = Error::const_new(ErrorKind::ResourceBusy, "stdin is already locked");
+        Ok(StdinLock { inner: STDIN.try_lock().ok_or(BUSY)? })
+    }
+
+    /// Fails with [`ErrorKind::ResourceBusy`] while a [`StdinLock`] is alive.
+    pub fn read_line(&self, buf: &mut String) -> io::Result<usize> {
+        self.try_lock()?.read_line(buf)
+    }
+
+    pub fn lines(self) -> Lines<StdinLock<'static>> {
+        self.lock().lines()
+    }
+
+    /// Whether the line is echoed to stdout as it's edited, on by default.
+    pub fn set_echo(&self, echo: bool) {
+        self.lock().inner.echo = echo;
+    }
+
+    /// Whether finished lines are remembered and can be recalled with up/down, off by default.
+    pub fn set_history(&self, enabled: bool) {
+        let mut lock = self.lock();
+        lock.inner.history_enabled = enabled;
+        if !enabled {
+            lock.inner.history.clear();
+        }
+    }
+}
+
+impl Read for Stdin {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.try_lock()?.read(buf)
+    }
+}
+
+impl BufRead for StdinLock<'_> {
+    // an empty buffer means ctrl+d was pressed on an empty line
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let state = &mut *self.inner;
+        if state.pos < state.pending.len() {
+            return Ok(&state.pending[state.pos..]);
+        }
+
+        let Some(mut line) = edit_line(&state.history, state.echo)? else {
+            return Ok(&[]);
+        };
+        if state.history_enabled && !line.is_empty() && state.history.last() != Some(&line) {
+            state.history.push(line.clone());
+        }
+
+        line.push('\n');
+        state.pending = line.into_bytes();
+        state.pos = 0;
+        Ok(&state.pending)
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let state = &mut *self.inner;
+        state.pos = (state.pos + amt).min(state.pending.len());
+    }
+}
+
+impl Read for StdinLock<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let n = buf.len().min(available.len());
+        buf[..n].copy_from_slice(&available[..n]);
+        self.consume(n);
+        Ok(n)
+    }
+}
+
+impl fmt::Debug for Stdin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stdin").finish_non_exhaustive()
+    }
+}
+
+fn next_key_press() -> KeyEvent {
+    loop {
+        match keyboard::read_event() {
+            Some(event) if matches!(event.ty, KeyEventType::KeyPress) => return event,
+            Some(_) => {},
+            None => thread::sleep(Duration::from_millis(1)),
+        }
+    }
+}
+
+// hid_to_char only knows the unshifted US layout
+fn shifted(c: char) -> char {
+    match c {
+        'a'..='z' => c.to_ascii_uppercase(),
+        '1' => '!', '2' => '@', '3' => '#', '4' => '$', '5' => '%',
+        '6' => '^', '7' => '&', '8' => '*', '9' => '(', '0' => ')',
+        '-' => '_', '=' => '+', '[' => '{', ']' => '}', '\\' => '|',
+        ';' => ':', '\'' => '"', '`' => '~', ',' => '<', '.' => '>',
+        '/' => '?', '#' => '~',
+        _ => c,
+    }
+}
+
+// the console only prints whole lines, so every edit prints the line again with
+// a `|` where the cursor is unless it's at the end
+fn draw(line: &[char], cursor: usize) -> io::Result<String> {
+    let mut shown: String = line[..cursor].iter().collect();
+    if cursor < line.len() {
+        shown.push('|');
+        shown.extend(&line[cursor..]);
+    }
+    io::stdout().write_fmt(format_args!("{}\n", shown))?;
+    Ok(shown)
+}
+
+// blocks until enter, returns None if ctrl+d is pressed on an empty line
+fn edit_line(history: &[String], echo: bool) -> io::Result<Option<String>> {
+    let mut line: Vec<char> = Vec::new();
+    let mut cursor = 0;
+    // index into history while browsing it, and the line that was being typed before
+    let mut browsing: Option<usize> = None;
+    let mut draft: Vec<char> = Vec::new();
+    // what was printed last, enter only prints the line if that isn't it already
+    let mut drawn: Option<String> = None;
+
+    loop {
+        let event = next_key_press();
+        let ctrl = event.modifier.intersects(ModifierKey::LCTRL | ModifierKey::RCTRL);
+        let shift = event.modifier.intersects(ModifierKey::LSHIFT | ModifierKey::RSHIFT);
+        let before = (line.clone(), cursor);
+
+        match event.key {
+            Key::KEY_ENTER | Key::KEY_KPENTER => {
+                let line: String = line.into_iter().collect();
+                if echo && drawn.as_ref() != Some(&line) {
+                    io::stdout().write_fmt(format_args!("{}\n", line))?;
+                }
+                return Ok(Some(line));
+            },
+            Key::KEY_D if ctrl && line.is_empty() => return Ok(None),
+            Key::KEY_BACKSPACE if cursor > 0 => {
+                cursor -= 1;
+                line.remove(cursor);
+            },
+            Key::KEY_DELETE if cursor < line.len() => {
+                line.remove(cursor);
+            },
+            Key::KEY_LEFT => cursor = cursor.saturating_sub(1),
+            Key::KEY_RIGHT => cursor = (cursor + 1).min(line.len()),
+            Key::KEY_HOME => cursor = 0,
+            Key::KEY_END => cursor = line.len(),
+            Key::KEY_UP if !history.is_empty() => {
+                let idx = match browsing {
+                    None => {
+                        draft = core::mem::take(&mut line);
+                        history.len() - 1
+                    },
+                    Some(idx) => idx.saturating_sub(1),
+                };
+                browsing = Some(idx);
+                line = history[idx].chars().collect();
+                cursor = line.len();
+            },
+            Key::KEY_DOWN => if let Some(idx) = browsing {
+                if idx + 1 < history.len() {
+                    browsing = Some(idx + 1);
+                    line = history[idx + 1].chars().collect();
+                } else {
+                    browsing = None;
+                    line = core::mem::take(&mut draft);
+                }
+                cursor = line.len();
+            },
+            key if !ctrl => if let Some(c) = key.to_char().filter(|c| *c == '\t' || !c.is_control()) {
+                line.insert(cursor, if shift { shifted(c) } else { c });
+                cursor += 1;
+            },
+            _ => {},
+        }
+
+        if echo && (line != before.0 || cursor != before.1) {
+            drawn = Some(draw(&line, cursor)?);
+        }
+    }
+}
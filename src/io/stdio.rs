@@ -0,0 +1,193 @@
+use core::fmt;
+
+use alloc::vec::Vec;
+
+use crate::{io::{self, ConsoleWriter, Write}, sync::{global, Global, TryLockGuard}};
+
+// printl always ends the line itself so output is held back until a full line is there
+type LineBuffer = Global<Vec<u8>>;
+
+global! {
+    static STDOUT: Vec<u8> = Vec::new();
+}
+global! {
+    static STDERR: Vec<u8> = Vec::new();
+}
+
+// a print from inside a Display impl or the panic handler finds the buffer
+// locked and collects its own line instead, printed when the lock is dropped
+fn lock_console(buffer: &'static LineBuffer) -> ConsoleLock {
+    ConsoleLock { buffer: buffer.try_lock(), local: Vec::new() }
+}
+
+fn print_line(line: &[u8]) -> io::Result<()> {
+    let mut out = ConsoleWriter::<256>::new();
+    out.write_bytes(line);
+    out.write_bytes(b"\n");
+    Ok(())
+}
+
+struct ConsoleLock {
+    buffer: Option<TryLockGuard<'static, Vec<u8>>>,
+    local: Vec<u8>,
+}
+
+impl ConsoleLock {
+    fn pending(&mut self) -> &mut Vec<u8> {
+        match self.buffer.as_deref_mut() {
+            Some(pending) => pending,
+            None => &mut self.local,
+        }
+    }
+}
+
+impl Write for ConsoleLock {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let pending = self.pending();
+        let Some(last_nl) = buf.iter().rposition(|&b| b == b'\n') else {
+            pending.extend_from_slice(buf);
+            return Ok(buf.len());
+        };
+
+        pending.extend_from_slice(&buf[..last_nl]);
+        let lines = core::mem::take(pending);
+        pending.extend_from_slice(&buf[last_nl + 1..]);
+        for line in lines.split(|&b| b == b'\n') {
+            print_line(line)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let pending = self.pending();
+        if !pending.is_empty() {
+            print_line(&core::mem::take(pending))?;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ConsoleLock {
+    fn drop(&mut self) {
+        if !self.local.is_empty() {
+            let _ = print_line(&self.local);
+        }
+    }
+}
+
+pub struct Stdout {
+    _priv: (),
+}
+
+pub struct StdoutLock<'a> {
+    inner: ConsoleLock,
+    _marker: core::marker::PhantomData<&'a ()>,
+}
+
+pub fn stdout() -> Stdout {
+    Stdout { _priv: () }
+}
+
+impl Stdout {
+    pub fn lock(&self) -> StdoutLock<'static> {
+        StdoutLock { inner: lock_console(&STDOUT), _marker: core::marker::PhantomData }
+    }
+}
+
+impl Write for Stdout {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.lock().flush()
+    }
+
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> io::Result<()> {
+        self.lock().write_fmt(fmt)
+    }
+}
+
+impl Write for StdoutLock<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl fmt::Debug for Stdout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stdout").finish_non_exhaustive()
+    }
+}
+
+// RedactedOS has a single console, stderr only has its own line buffer so
+// partial lines from stdout and stderr don't end up on the same line
+pub struct Stderr {
+    _priv: (),
+}
+
+pub struct StderrLock<'a> {
+    inner: ConsoleLock,
+    _marker: core::marker::PhantomData<&'a ()>,
+}
+
+pub fn stderr() -> Stderr {
+    Stderr { _priv: () }
+}
+
+impl Stderr {
+    pub fn lock(&self) -> StderrLock<'static> {
+        StderrLock { inner: lock_console(&STDERR), _marker: core::marker::PhantomData }
+    }
+}
+
+impl Write for Stderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.lock().flush()
+    }
+
+    fn write_fmt(&mut self, fmt: fmt::Arguments<'_>) -> io::Result<()> {
+        self.lock().write_fmt(fmt)
+    }
+}
+
+impl Write for StderrLock<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl fmt::Debug for Stderr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stderr").finish_non_exhaustive()
+    }
+}
+
+#[doc(hidden)]
+pub fn _print(args: fmt::Arguments<'_>) {
+    if let Err(e) = stdout().write_fmt(args) {
+        panic!("failed printing to stdout: {e}");
+    }
+}
+
+#[doc(hidden)]
+pub fn _eprint(args: fmt::Arguments<'_>) {
+    if let Err(e) = stderr().write_fmt(args) {
+        panic!("failed printing to stderr: {e}");
+    }
+}
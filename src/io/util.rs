@@ -0,0 +1,93 @@
+use core::fmt;
+
+use alloc::vec;
+
+use crate::io::{self, buffered::DEFAULT_BUF_SIZE, BufRead, Read, Seek, SeekFrom, Write};
+
+pub fn copy<R: Read + ?Sized, W: Write + ?Sized>(reader: &mut R, writer: &mut W) -> io::Result<u64> {
+    let mut buf = vec![0u8; DEFAULT_BUF_SIZE];
+    let mut written = 0;
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            return Ok(written);
+        }
+        writer.write_all(&buf[..n])?;
+        written += n as u64;
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Empty;
+
+pub const fn empty() -> Empty {
+    Empty
+}
+
+impl Read for Empty {
+    fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+        Ok(0)
+    }
+}
+
+impl BufRead for Empty {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(&[])
+    }
+
+    fn consume(&mut self, _amt: usize) {}
+}
+
+impl Seek for Empty {
+    fn seek(&mut self, _pos: SeekFrom) -> io::Result<u64> {
+        Ok(0)
+    }
+}
+
+impl Write for Empty {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+pub struct Repeat {
+    byte: u8,
+}
+
+pub const fn repeat(byte: u8) -> Repeat {
+    Repeat { byte }
+}
+
+impl Read for Repeat {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        buf.fill(self.byte);
+        Ok(buf.len())
+    }
+}
+
+impl fmt::Debug for Repeat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Repeat").finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Sink;
+
+pub const fn sink() -> Sink {
+    Sink
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
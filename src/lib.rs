@@ -1,9 +1,14 @@
 #![no_std]
 #![allow(internal_features)]
-#![feature(lang_items, never_type)]
+// `never_type` is stable on recent nightlies, older ones still need it for `impl Termination for !`
+#![allow(stable_features)]
+#![feature(never_type)]
+#![cfg_attr(not(feature = "mock"), feature(lang_items))]
 
 pub extern crate core;
 pub extern crate alloc;
+#[cfg(feature = "mock")]
+extern crate std;
 
 #[doc(hidden)]
 #[macro_use]
@@ -15,10 +20,21 @@ pub mod process;
 pub mod thread;
 pub mod time;
 pub extern crate unix_path as path;
+pub extern crate unix_str;
 pub mod fs;
+pub mod env;
+pub mod archive;
+pub mod flate;
 
 pub mod backtrace;
 pub mod random;
 
+mod sync;
+#[cfg(not(feature = "proposed-abi"))]
+mod proposed;
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
 #[macro_use]
 mod macros;
\ No newline at end of file
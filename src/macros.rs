@@ -1,7 +1,48 @@
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::io::_print(format_args!($($arg)*))
+    };
+}
+
 #[macro_export]
 macro_rules! println {
-    () => { $crate::io::putnl(); };
+    () => { $crate::print!("\n") };
+    ($($arg:tt)*) => {
+        $crate::io::_print(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+#[macro_export]
+macro_rules! eprint {
     ($($arg:tt)*) => {
-        $crate::io::putfmt(format_args!($($arg)*)).unwrap();
-    }
-}
\ No newline at end of file
+        $crate::io::_eprint(format_args!($($arg)*))
+    };
+}
+
+#[macro_export]
+macro_rules! eprintln {
+    () => { $crate::eprint!("\n") };
+    ($($arg:tt)*) => {
+        $crate::io::_eprint(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+#[macro_export]
+macro_rules! dbg {
+    () => {
+        $crate::eprintln!("[{}:{}:{}]", file!(), line!(), column!())
+    };
+    ($val:expr $(,)?) => {
+        // match keeps temporaries in $val alive, same as std's dbg!
+        match $val {
+            tmp => {
+                $crate::eprintln!("[{}:{}:{}] {} = {:#?}", file!(), line!(), column!(), stringify!($val), &tmp);
+                tmp
+            }
+        }
+    };
+    ($($val:expr),+ $(,)?) => {
+        ($($crate::dbg!($val)),+,)
+    };
+}
@@ -0,0 +1,12 @@
+use std::alloc::{Layout, System, GlobalAlloc};
+
+// RedactedOS' malloc doesn't take an alignment, 16 covers everything the allocator asks for
+const ALIGN: usize = 16;
+
+pub unsafe fn malloc(size: usize) -> *mut u8 {
+    unsafe { System.alloc(Layout::from_size_align_unchecked(size.max(1), ALIGN)) }
+}
+
+pub unsafe fn free(ptr: *mut u8, size: usize) {
+    unsafe { System.dealloc(ptr, Layout::from_size_align_unchecked(size.max(1), ALIGN)) }
+}
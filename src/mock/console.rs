@@ -0,0 +1,26 @@
+use core::{cell::RefCell, ffi::{c_char, CStr}};
+
+use alloc::{string::{String, ToString}, vec::Vec};
+
+std::thread_local! {
+    static LINES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+pub unsafe fn ffi_printl(str: *const c_char) {
+    let line = unsafe { CStr::from_ptr(str) }.to_string_lossy().to_string();
+    LINES.with_borrow_mut(|lines| lines.push(line));
+}
+
+/// Takes every line printed through `printl` so far.
+pub fn take_lines() -> Vec<String> {
+    LINES.take()
+}
+
+/// Takes everything printed through `printl` so far with each line terminated by `\n`.
+pub fn take_output() -> String {
+    take_lines().into_iter().fold(String::new(), |mut out, line| {
+        out.push_str(&line);
+        out.push('\n');
+        out
+    })
+}
@@ -0,0 +1,494 @@
+use core::{cell::RefCell, ffi::{c_char, CStr}};
+
+use alloc::{collections::BTreeMap, string::{String, ToString}, vec::Vec};
+
+use crate::fs::{FileDescriptor, FsResult, OpenFlags, RawMetadata, SeekType, UNSUPPORTED};
+
+struct Node {
+    data: Vec<u8>,
+    // streams report a size of 0 and hand out their data once, like RedactedOS' virtual files
+    stream: bool,
+    dir: bool,
+    // raw code fopen returns instead of opening, may be one RedactedOS doesn't define
+    fail: Option<i32>,
+}
+
+#[derive(Default)]
+struct State {
+    nodes: BTreeMap<String, Node>,
+    open: BTreeMap<u64, (String, OpenFlags)>,
+    next_id: u64,
+    readonly: bool,
+    syncs: usize,
+    reads: usize,
+    fstats: usize,
+    read_limit: Option<usize>,
+    fstat_fail: Option<i32>,
+    bad_closes: usize,
+    // the `proposed-abi` calls fail like the stand-ins in `crate::proposed`
+    no_proposed_abi: bool,
+}
+
+std::thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// Adds a regular file at `path`, replacing whatever was there.
+pub fn add_file(path: &str, data: impl Into<Vec<u8>>) {
+    STATE.with_borrow_mut(|s| s.nodes.insert(path.to_string(), Node { data: data.into(), stream: false, dir: false, fail: None }));
+}
+
+/// Adds a virtual unsized file at `path` that yields `data` once.
+pub fn add_stream(path: &str, data: impl Into<Vec<u8>>) {
+    STATE.with_borrow_mut(|s| s.nodes.insert(path.to_string(), Node { data: data.into(), stream: true, dir: false, fail: None }));
+}
+
+/// Adds an empty directory at `path`, directories also exist implicitly above any file.
+pub fn add_dir(path: &str) {
+    STATE.with_borrow_mut(|s| s.nodes.insert(path.to_string(), Node { data: Vec::new(), stream: false, dir: true, fail: None }));
+}
+
+/// Makes `fopen` on `path` return the raw result `code`.
+pub fn add_failing(path: &str, code: i32) {
+    STATE.with_borrow_mut(|s| s.nodes.insert(path.to_string(), Node { data: Vec::new(), stream: false, dir: false, fail: Some(code) }));
+}
+
+/// Makes every open that could modify a file fail like on a read-only mount.
+pub fn set_readonly(readonly: bool) {
+    STATE.with_borrow_mut(|s| s.readonly = readonly);
+}
+
+/// Number of `fsync` calls so far.
+pub fn sync_count() -> usize {
+    STATE.with_borrow(|s| s.syncs)
+}
+
+/// Number of `fclose` calls on descriptors that weren't open.
+pub fn bad_close_count() -> usize {
+    STATE.with_borrow(|s| s.bad_closes)
+}
+
+/// Makes every `fread` return at most `limit` bytes, like a driver that reads a block at a time.
+pub fn set_read_limit(limit: Option<usize>) {
+    STATE.with_borrow_mut(|s| s.read_limit = limit);
+}
+
+/// Makes every `fstat` return the raw result `code` instead.
+pub fn set_fstat_failing(code: Option<i32>) {
+    STATE.with_borrow_mut(|s| s.fstat_fail = code);
+}
+
+/// With `false`, the calls only linked with the `proposed-abi` feature fail with
+/// `ErrorKind::Unsupported` like they do without it, and `fwrite` writes nothing.
+pub fn set_proposed_abi(enabled: bool) {
+    STATE.with_borrow_mut(|s| s.no_proposed_abi = !enabled);
+}
+
+fn unsupported() -> bool {
+    STATE.with_borrow(|s| s.no_proposed_abi)
+}
+
+/// Number of `fstat` calls so far.
+pub fn fstat_count() -> usize {
+    STATE.with_borrow(|s| s.fstats)
+}
+
+/// Number of `fread` calls so far.
+pub fn read_count() -> usize {
+    STATE.with_borrow(|s| s.reads)
+}
+
+/// Removes whatever is at `path`.
+pub fn remove(path: &str) {
+    STATE.with_borrow_mut(|s| s.nodes.remove(path));
+}
+
+/// Returns the contents of the file at `path`.
+pub fn contents(path: &str) -> Option<Vec<u8>> {
+    STATE.with_borrow(|s| s.nodes.get(path).map(|node| node.data.clone()))
+}
+
+/// Number of descriptors that are currently open.
+pub fn open_count() -> usize {
+    STATE.with_borrow(|s| s.open.len())
+}
+
+pub(crate) fn clear() {
+    STATE.take();
+}
+
+impl State {
+    fn is_dir(&self, path: &str) -> bool {
+        let prefix = dir_prefix(path);
+        path == "/" || self.nodes.get(path).is_some_and(|node| node.dir) || self.nodes.keys().any(|key| key.starts_with(&prefix))
+    }
+
+    // names and raw kinds of what's directly inside `path`
+    fn children(&self, path: &str) -> BTreeMap<String, u8> {
+        let prefix = dir_prefix(path);
+        let mut children = BTreeMap::new();
+        for (key, node) in self.nodes.range(prefix.clone()..).take_while(|(key, _)| key.starts_with(&prefix)) {
+            let rest = &key[prefix.len()..];
+            let (name, nested) = match rest.split_once('/') {
+                Some((name, _)) => (name, true),
+                None => (rest, false),
+            };
+            let kind = if nested || node.dir { 2 } else if node.stream { 3 } else { 1 };
+            if !name.is_empty() {
+                children.insert(name.to_string(), kind);
+            }
+        }
+        children
+    }
+
+    // directories that only existed because of what's inside them stay when it goes
+    fn keep_parent(&mut self, path: &str) {
+        if let Some((parent, _)) = path.rsplit_once('/')
+            && !parent.is_empty()
+        {
+            self.nodes.entry(parent.to_string()).or_insert(Node { data: Vec::new(), stream: false, dir: true, fail: None });
+        }
+    }
+
+    // whether something can be created at `path`
+    fn check_parent(&self, path: &str) -> Result<(), FsResult> {
+        let parent = match path.rsplit_once('/') {
+            Some(("", _)) | None => "/",
+            Some((parent, _)) => parent,
+        };
+        if self.is_dir(parent) {
+            Ok(())
+        } else if self.nodes.contains_key(parent) {
+            Err(FsResult::NotADirectory)
+        } else {
+            Err(FsResult::NotFound)
+        }
+    }
+
+    fn stat(&self, path: &str) -> Option<RawMetadata> {
+        let readonly = self.readonly as u8;
+        match self.nodes.get(path) {
+            Some(node) if node.dir => Some(RawMetadata { size: 0, kind: 2, readonly }),
+            Some(node) if node.stream => Some(RawMetadata { size: 0, kind: 3, readonly }),
+            Some(node) => Some(RawMetadata { size: node.data.len() as u64, kind: 1, readonly }),
+            None if self.is_dir(path) => Some(RawMetadata { size: 0, kind: 2, readonly }),
+            None => None,
+        }
+    }
+}
+
+// trailing slashes don't matter, like on a real filesystem
+unsafe fn read_path(path: *const c_char) -> String {
+    let path = unsafe { CStr::from_ptr(path) }.to_string_lossy();
+    match path.trim_end_matches('/') {
+        "" => "/".to_string(),
+        path => path.to_string(),
+    }
+}
+
+fn dir_prefix(path: &str) -> String {
+    let mut prefix = path.trim_end_matches('/').to_string();
+    prefix.push('/');
+    prefix
+}
+
+pub unsafe fn ffi_fopen(path: *const c_char, descriptor: *mut FileDescriptor) -> i32 {
+    unsafe { open(path, OpenFlags::READ, descriptor) }
+}
+
+pub unsafe fn ffi_fopen_mode(path: *const c_char, flags: OpenFlags, descriptor: *mut FileDescriptor) -> i32 {
+    if unsupported() {
+        return UNSUPPORTED;
+    }
+    unsafe { open(path, flags, descriptor) }
+}
+
+unsafe fn open(path: *const c_char, flags: OpenFlags, descriptor: *mut FileDescriptor) -> i32 {
+    let path = unsafe { read_path(path) };
+    STATE.with_borrow_mut(|s| {
+        if s.readonly && flags != OpenFlags::READ {
+            return FsResult::ReadOnly as i32;
+        }
+        if flags.contains(OpenFlags::CREATE_NEW) && (s.nodes.contains_key(&path) || s.is_dir(&path)) {
+            return FsResult::AlreadyExists as i32;
+        }
+        if !s.nodes.contains_key(&path) && s.is_dir(&path) {
+            return FsResult::IsADirectory as i32;
+        }
+        if flags.intersects(OpenFlags::CREATE | OpenFlags::CREATE_NEW) && !s.nodes.contains_key(&path) {
+            if let Err(res) = s.check_parent(&path) {
+                return res as i32;
+            }
+            s.nodes.insert(path.clone(), Node { data: Vec::new(), stream: false, dir: false, fail: None });
+        }
+        let Some(node) = s.nodes.get_mut(&path) else {
+            return FsResult::NotFound as i32;
+        };
+        if let Some(code) = node.fail {
+            return code;
+        }
+        if node.dir {
+            return FsResult::IsADirectory as i32;
+        }
+        if flags.contains(OpenFlags::TRUNCATE) {
+            node.data.clear();
+        }
+        let size = if node.stream { 0 } else { node.data.len() as u64 };
+        s.next_id += 1;
+        let id = s.next_id;
+        s.open.insert(id, (path, flags));
+        unsafe { *descriptor = FileDescriptor::from_raw(size, id); }
+        FsResult::Success as i32
+    })
+}
+
+pub unsafe fn ffi_fclose(descriptor: *const FileDescriptor) {
+    let id = unsafe { (*descriptor).id };
+    STATE.with_borrow_mut(|s| {
+        if s.open.remove(&id).is_none() {
+            s.bad_closes += 1;
+        }
+    });
+}
+
+pub unsafe fn ffi_fseek(descriptor: *mut FileDescriptor, offset: i64, ty: SeekType) {
+    let descriptor = unsafe { &mut *descriptor };
+    let cursor = match ty {
+        SeekType::Absolute => offset.max(0) as u64,
+        SeekType::Relative => descriptor.cursor.saturating_add_signed(offset),
+    };
+    descriptor.cursor = cursor.min(descriptor.size);
+}
+
+pub unsafe fn ffi_fread(descriptor: *mut FileDescriptor, buf: *mut c_char, size: u64) -> u64 {
+    let descriptor = unsafe { &mut *descriptor };
+    STATE.with_borrow_mut(|s| {
+        s.reads += 1;
+        let size = s.read_limit.map_or(size, |limit| size.min(limit as u64));
+        let Some(node) = s.open.get(&descriptor.id).and_then(|(path, _)| s.nodes.get_mut(path)) else {
+            return 0;
+        };
+        let src = if node.stream {
+            let n = (size as usize).min(node.data.len());
+            node.data.drain(..n).collect()
+        } else {
+            let start = (descriptor.cursor as usize).min(node.data.len());
+            let end = start.saturating_add(size as usize).min(node.data.len());
+            node.data[start..end].to_vec()
+        };
+        unsafe { core::ptr::copy_nonoverlapping(src.as_ptr(), buf as *mut u8, src.len()); }
+        descriptor.cursor += src.len() as u64;
+        src.len() as u64
+    })
+}
+
+pub unsafe fn ffi_fwrite(descriptor: *mut FileDescriptor, buf: *const c_char, size: u64) -> u64 {
+    if unsupported() {
+        return 0;
+    }
+    let descriptor = unsafe { &mut *descriptor };
+    let src = unsafe { core::slice::from_raw_parts(buf as *const u8, size as usize) };
+    STATE.with_borrow_mut(|s| {
+        let Some((path, flags)) = s.open.get(&descriptor.id) else {
+            return 0;
+        };
+        let Some(node) = s.nodes.get_mut(path).filter(|_| flags.intersects(OpenFlags::WRITE | OpenFlags::APPEND)) else {
+            return 0;
+        };
+        let start = if flags.contains(OpenFlags::APPEND) { node.data.len() } else { descriptor.cursor as usize };
+        let end = start + src.len();
+        if node.data.len() < end {
+            node.data.resize(end, 0);
+        }
+        node.data[start..end].copy_from_slice(src);
+        descriptor.cursor = end as u64;
+        descriptor.size = node.data.len() as u64;
+        size
+    })
+}
+
+pub unsafe fn ffi_fsync(descriptor: *const FileDescriptor) -> i32 {
+    if unsupported() {
+        return UNSUPPORTED;
+    }
+    let id = unsafe { (*descriptor).id };
+    STATE.with_borrow_mut(|s| {
+        if !s.open.contains_key(&id) {
+            return FsResult::DriverError as i32;
+        }
+        s.syncs += 1;
+        FsResult::Success as i32
+    })
+}
+
+pub unsafe fn ffi_dir_list(path: *const c_char, buf: *mut c_char, size: u64, offset: *mut u64) -> i32 {
+    if unsupported() {
+        return UNSUPPORTED;
+    }
+    let path = unsafe { read_path(path) };
+    let buf = unsafe { core::slice::from_raw_parts_mut(buf as *mut u8, size as usize) };
+    let offset = unsafe { &mut *offset };
+    STATE.with_borrow(|s| {
+        if !s.is_dir(&path) {
+            return if s.nodes.contains_key(&path) { FsResult::NotADirectory } else { FsResult::NotFound } as i32;
+        }
+        let mut pos = 0;
+        for (name, kind) in s.children(&path).into_iter().skip(*offset as usize) {
+            let len = name.len() + 2;
+            if pos + len > buf.len() {
+                if pos == 0 {
+                    return FsResult::BufferTooSmall as i32;
+                }
+                break;
+            }
+            buf[pos] = kind;
+            buf[pos + 1..pos + len - 1].copy_from_slice(name.as_bytes());
+            buf[pos + len - 1] = 0;
+            pos += len;
+            *offset += 1;
+        }
+        FsResult::Success as i32
+    })
+}
+
+pub unsafe fn ffi_stat(path: *const c_char, metadata: *mut RawMetadata) -> i32 {
+    if unsupported() {
+        return UNSUPPORTED;
+    }
+    let path = unsafe { read_path(path) };
+    match STATE.with_borrow(|s| s.stat(&path)) {
+        Some(raw) => {
+            unsafe { *metadata = raw; }
+            FsResult::Success as i32
+        },
+        None => FsResult::NotFound as i32,
+    }
+}
+
+pub unsafe fn ffi_fstat(descriptor: *const FileDescriptor, metadata: *mut RawMetadata) -> i32 {
+    STATE.with_borrow_mut(|s| s.fstats += 1);
+    if unsupported() {
+        return UNSUPPORTED;
+    }
+    let id = unsafe { (*descriptor).id };
+    if let Some(code) = STATE.with_borrow(|s| s.fstat_fail) {
+        return code;
+    }
+    match STATE.with_borrow(|s| s.open.get(&id).and_then(|(path, _)| s.stat(path))) {
+        Some(raw) => {
+            unsafe { *metadata = raw; }
+            FsResult::Success as i32
+        },
+        None => FsResult::DriverError as i32,
+    }
+}
+
+pub unsafe fn ffi_mkdir(path: *const c_char) -> i32 {
+    if unsupported() {
+        return UNSUPPORTED;
+    }
+    let path = unsafe { read_path(path) };
+    STATE.with_borrow_mut(|s| {
+        if s.readonly {
+            return FsResult::ReadOnly as i32;
+        }
+        if s.nodes.contains_key(&path) || s.is_dir(&path) {
+            return FsResult::AlreadyExists as i32;
+        }
+        if let Err(res) = s.check_parent(&path) {
+            return res as i32;
+        }
+        s.nodes.insert(path, Node { data: Vec::new(), stream: false, dir: true, fail: None });
+        FsResult::Success as i32
+    })
+}
+
+pub unsafe fn ffi_rmdir(path: *const c_char) -> i32 {
+    if unsupported() {
+        return UNSUPPORTED;
+    }
+    let path = unsafe { read_path(path) };
+    STATE.with_borrow_mut(|s| {
+        if s.readonly {
+            return FsResult::ReadOnly as i32;
+        }
+        if !s.is_dir(&path) {
+            return if s.nodes.contains_key(&path) { FsResult::NotADirectory } else { FsResult::NotFound } as i32;
+        }
+        if path == "/" {
+            return FsResult::DriverError as i32;
+        }
+        if !s.children(&path).is_empty() {
+            return FsResult::DirectoryNotEmpty as i32;
+        }
+        s.nodes.remove(&path);
+        s.keep_parent(&path);
+        FsResult::Success as i32
+    })
+}
+
+pub unsafe fn ffi_unlink(path: *const c_char) -> i32 {
+    if unsupported() {
+        return UNSUPPORTED;
+    }
+    let path = unsafe { read_path(path) };
+    STATE.with_borrow_mut(|s| {
+        if s.readonly {
+            return FsResult::ReadOnly as i32;
+        }
+        if s.is_dir(&path) {
+            return FsResult::IsADirectory as i32;
+        }
+        if s.nodes.remove(&path).is_none() {
+            return FsResult::NotFound as i32;
+        }
+        s.keep_parent(&path);
+        FsResult::Success as i32
+    })
+}
+
+pub unsafe fn ffi_rename(from: *const c_char, to: *const c_char) -> i32 {
+    if unsupported() {
+        return UNSUPPORTED;
+    }
+    let (from, to) = unsafe { (read_path(from), read_path(to)) };
+    STATE.with_borrow_mut(|s| {
+        if s.readonly {
+            return FsResult::ReadOnly as i32;
+        }
+        let from_dir = s.is_dir(&from);
+        if !from_dir && !s.nodes.contains_key(&from) {
+            return FsResult::NotFound as i32;
+        }
+        if from == to {
+            return FsResult::Success as i32;
+        }
+        if let Err(res) = s.check_parent(&to) {
+            return res as i32;
+        }
+        match (from_dir, s.is_dir(&to), s.nodes.contains_key(&to)) {
+            (false, true, _) => return FsResult::IsADirectory as i32,
+            (true, false, true) => return FsResult::NotADirectory as i32,
+            (true, true, _) if !s.children(&to).is_empty() => return FsResult::DirectoryNotEmpty as i32,
+            _ => {},
+        }
+        if from_dir && to.starts_with(&dir_prefix(&from)) {
+            return FsResult::DriverError as i32;
+        }
+
+        s.nodes.remove(&to);
+        s.keep_parent(&from);
+        let from_prefix = dir_prefix(&from);
+        let moved: Vec<String> = s.nodes.keys().filter(|key| **key == from || key.starts_with(&from_prefix)).cloned().collect();
+        for key in moved {
+            let node = s.nodes.remove(&key).unwrap();
+            s.nodes.insert(alloc::format!("{to}{}", &key[from.len()..]), node);
+        }
+        for (path, _) in s.open.values_mut() {
+            if *path == from || path.starts_with(&from_prefix) {
+                *path = alloc::format!("{to}{}", &path[from.len()..]);
+            }
+        }
+        FsResult::Success as i32
+    })
+}
@@ -0,0 +1,126 @@
+use core::cell::RefCell;
+
+use alloc::collections::VecDeque;
+
+use crate::io::input::{keyboard::{KeyEvent, KeyEventType, Keypress, RawKeyEvent, RawKeypress}, keycodes::{Key, ModifierKey}};
+
+#[derive(Default)]
+struct State {
+    events: VecDeque<RawKeyEvent>,
+    keys: VecDeque<RawKeypress>,
+}
+
+std::thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+}
+
+/// Queues an event for `read_event`.
+pub fn push_event(event: KeyEvent) {
+    push_raw_event(event.into());
+}
+
+/// Queues an event for `read_event` as the C side would write it, values don't have to be valid.
+pub fn push_raw_event(event: RawKeyEvent) {
+    STATE.with_borrow_mut(|s| s.events.push_back(event));
+}
+
+/// Queues a report for `read_key`.
+pub fn push_keypress(keypress: Keypress) {
+    push_raw_keypress(keypress.into());
+}
+
+/// Queues a report for `read_key` as the C side would write it.
+pub fn push_raw_keypress(keypress: RawKeypress) {
+    STATE.with_borrow_mut(|s| s.keys.push_back(keypress));
+}
+
+/// Queues a press and a release of `key`.
+pub fn press(key: Key) {
+    push_event(KeyEvent { ty: KeyEventType::KeyPress, key, modifier: ModifierKey::none() });
+    push_event(KeyEvent { ty: KeyEventType::KeyRelease, key, modifier: ModifierKey::none() });
+}
+
+/// Queues a press and a release of `key` while `modifier` is held.
+pub fn press_with(key: Key, modifier: ModifierKey) {
+    push_event(KeyEvent { ty: KeyEventType::KeyPress, key, modifier });
+    push_event(KeyEvent { ty: KeyEventType::KeyRelease, key, modifier });
+}
+
+/// Queues the key presses to type `text` on a US layout, `\n` presses enter.
+///
+/// Panics on characters that can't be typed.
+pub fn type_str(text: &str) {
+    for c in text.chars() {
+        let (hid, shift) = (0x04..=0x38)
+            .find_map(|hid| {
+                let plain = unsafe { ffi_hid_to_char(hid) } as char;
+                if plain == c {
+                    Some((hid, false))
+                } else if shifted(plain) == Some(c) {
+                    Some((hid, true))
+                } else {
+                    None
+                }
+            })
+            .unwrap_or_else(|| panic!("can't type {c:?}"));
+        press_with(Key::from_hid(hid), if shift { ModifierKey::LSHIFT } else { ModifierKey::none() });
+    }
+}
+
+fn shifted(c: char) -> Option<char> {
+    const PLAIN: &str = "1234567890-=[]\\;'`,./";
+    const SHIFTED: &str = "!@#$%^&*()_+{}|:\"~<>?";
+    if c.is_ascii_lowercase() {
+        return Some(c.to_ascii_uppercase());
+    }
+    PLAIN.find(c).and_then(|idx| SHIFTED.chars().nth(idx))
+}
+
+pub(crate) fn clear() {
+    STATE.take();
+}
+
+pub unsafe fn ffi_read_key(kp: *mut RawKeypress) -> bool {
+    match STATE.with_borrow_mut(|s| s.keys.pop_front()) {
+        Some(keypress) => {
+            unsafe { *kp = keypress; }
+            true
+        },
+        None => false,
+    }
+}
+
+pub unsafe fn ffi_read_event(event: *mut RawKeyEvent) -> bool {
+    match STATE.with_borrow_mut(|s| s.events.pop_front()) {
+        Some(ev) => {
+            unsafe { *event = ev; }
+            true
+        },
+        None => false,
+    }
+}
+
+// US layout without shift, which is what RedactedOS' table covers
+pub unsafe fn ffi_hid_to_char(hid: u8) -> u8 {
+    match hid {
+        0x04..=0x1d => b'a' + (hid - 0x04),
+        0x1e..=0x26 => b'1' + (hid - 0x1e),
+        0x27 => b'0',
+        0x28 => b'\n',
+        0x2b => b'\t',
+        0x2c => b' ',
+        0x2d => b'-',
+        0x2e => b'=',
+        0x2f => b'[',
+        0x30 => b']',
+        0x31 => b'\\',
+        0x32 => b'#',
+        0x33 => b';',
+        0x34 => b'\'',
+        0x35 => b'`',
+        0x36 => b',',
+        0x37 => b'.',
+        0x38 => b'/',
+        _ => 0,
+    }
+}
@@ -0,0 +1,31 @@
+//! In-process stand-in for the RedactedOS shared library.
+//!
+//! Enabled by the `mock` feature, every `ffi_*` function the crate would normally link
+//! against RedactedOS is re-exported from here instead so the crate can be tested on the host.
+//! All state is thread local, every test thread starts out with an empty filesystem,
+//! an empty keyboard queue, no programs, a clock at 0 and no captured output. The
+//! same goes for what the crate itself keeps per process, like stdin, the current
+//! directory, mounts, arguments and exit hooks.
+//!
+//! The `mock` feature implies `proposed-abi`, [`fs::set_proposed_abi`] turns those
+//! calls off again to test what apps get on today's RedactedOS.
+
+// the `ffi_*` functions mirror the unsafe extern declarations they stand in for
+#![allow(clippy::missing_safety_doc)]
+
+pub mod alloc;
+pub mod console;
+pub mod fs;
+pub mod keyboard;
+pub mod process;
+pub mod time;
+
+/// Resets all simulated state of the current thread, crate state included.
+pub fn reset() {
+    console::take_lines();
+    fs::clear();
+    keyboard::clear();
+    process::clear();
+    time::set_millis(0);
+    crate::sync::reset_globals();
+}
@@ -0,0 +1,166 @@
+use core::{cell::{Cell, RefCell}, ffi::{c_char, CStr}};
+
+use alloc::{collections::BTreeMap, ffi::CString, string::{String, ToString}, vec::Vec};
+use std::{boxed::Box, panic::{self, UnwindSafe}};
+
+use crate::fs::FsResult;
+
+/// Payload `halt` unwinds with, carrying the exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Exit(pub u32);
+
+pub unsafe fn ffi_exit(exit_code: u32) -> ! {
+    // resume_unwind skips the panic hook so nothing gets printed for a normal exit
+    panic::resume_unwind(Box::new(Exit(exit_code)))
+}
+
+/// Runs `f`, returning `Err` with the exit code if it halted the simulated process.
+pub fn catch_exit<R, F: FnOnce() -> R + UnwindSafe>(f: F) -> Result<R, u32> {
+    match panic::catch_unwind(f) {
+        Ok(ret) => Ok(ret),
+        Err(payload) => match payload.downcast::<Exit>() {
+            Ok(exit) => Err(exit.0),
+            Err(payload) => panic::resume_unwind(payload),
+        },
+    }
+}
+
+/// Runs `main` the way RedactedOS starts a Rust program, without arguments, and
+/// returns the code it exits with.
+pub fn run_main<T: crate::process::Termination + 'static>(main: fn() -> T) -> isize {
+    crate::rt::lang_start(main, 0, core::ptr::null(), 0)
+}
+
+/// What a simulated program was started with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spawn {
+    pub path: String,
+    pub args: Vec<String>,
+    pub env: Vec<String>,
+    pub cwd: String,
+}
+
+#[derive(Clone, Copy)]
+struct Program {
+    main: fn(&Spawn) -> i32,
+    // `try_wait` calls answered with "still running" before it exits
+    polls: u32,
+}
+
+struct Process {
+    program: Program,
+    polls: u32,
+    spawn: Spawn,
+}
+
+#[derive(Default)]
+struct State {
+    programs: BTreeMap<String, Program>,
+    processes: BTreeMap<u32, Process>,
+    spawned: Vec<Spawn>,
+    next_pid: u32,
+}
+
+std::thread_local! {
+    static STATE: RefCell<State> = RefCell::new(State::default());
+    // what `get_environ` hands a simulated child
+    static ENVIRON: Cell<*const *const c_char> = const { Cell::new(core::ptr::null()) };
+}
+
+/// Makes `path` a program that exits with what `main` returns, after answering
+/// `polls` calls to `try_wait` with "still running".
+///
+/// `main` runs on a thread of its own once the program is waited for, so it starts
+/// out with fresh simulated state, and with its arguments, environment and current
+/// directory set up the way a Rust program's start-up code does it. Calling
+/// `process::exit` in there ends the child with that code.
+pub fn add_program(path: &str, polls: u32, main: fn(&Spawn) -> i32) {
+    STATE.with_borrow_mut(|s| s.programs.insert(path.to_string(), Program { main, polls }));
+}
+
+/// Everything spawned on this thread so far, oldest first.
+pub fn spawned() -> Vec<Spawn> {
+    STATE.with_borrow(|s| s.spawned.clone())
+}
+
+/// Number of processes that haven't been waited for.
+pub fn running_count() -> usize {
+    STATE.with_borrow(|s| s.processes.len())
+}
+
+pub(crate) fn clear() {
+    STATE.take();
+}
+
+unsafe fn strings(list: *const *const c_char, len: u64) -> Vec<String> {
+    (0..len as usize).map(|i| unsafe { CStr::from_ptr(*list.add(i)) }.to_string_lossy().to_string()).collect()
+}
+
+pub unsafe fn ffi_spawn(path: *const c_char, argv: *const *const c_char, argc: u64, envp: *const *const c_char, envc: u64, cwd: *const c_char, pid: *mut u32) -> i32 {
+    let spawn = unsafe {
+        Spawn {
+            path: CStr::from_ptr(path).to_string_lossy().to_string(),
+            args: strings(argv, argc),
+            env: strings(envp, envc),
+            cwd: CStr::from_ptr(cwd).to_string_lossy().to_string(),
+        }
+    };
+    STATE.with_borrow_mut(|s| {
+        let Some(&program) = s.programs.get(&spawn.path) else {
+            return FsResult::NotFound as i32;
+        };
+        s.next_pid += 1;
+        unsafe { *pid = s.next_pid; }
+        s.spawned.push(spawn.clone());
+        s.processes.insert(s.next_pid, Process { program, polls: program.polls, spawn });
+        FsResult::Success as i32
+    })
+}
+
+pub unsafe fn ffi_wait(pid: u32, status: *mut i32) -> i32 {
+    let Some(process) = STATE.with_borrow_mut(|s| s.processes.remove(&pid)) else {
+        return FsResult::NotFound as i32;
+    };
+    unsafe { *status = run_child(process.program.main, process.spawn); }
+    FsResult::Success as i32
+}
+
+fn run_child(main: fn(&Spawn) -> i32, spawn: Spawn) -> i32 {
+    let child = std::thread::spawn(move || {
+        let strings = |list: &[String]| list.iter().map(|s| CString::new(s.as_str()).unwrap()).collect::<Vec<_>>();
+        let (argv, envp) = (strings(&spawn.args), strings(&spawn.env));
+        let argv_ptrs: Vec<_> = argv.iter().map(|arg| arg.as_ptr()).chain([core::ptr::null()]).collect();
+        let envp_ptrs: Vec<_> = envp.iter().map(|var| var.as_ptr()).chain([core::ptr::null()]).collect();
+        ENVIRON.set(envp_ptrs.as_ptr());
+        unsafe { crate::rt::init(argv.len() as isize, argv_ptrs.as_ptr() as _); }
+        ENVIRON.set(core::ptr::null());
+        catch_exit(|| main(&spawn)).unwrap_or_else(|code| code as i32)
+    });
+    child.join().unwrap_or_else(|payload| panic::resume_unwind(payload))
+}
+
+pub unsafe fn ffi_get_environ() -> *const *const c_char {
+    ENVIRON.get()
+}
+
+pub unsafe fn ffi_try_wait(pid: u32, status: *mut i32, exited: *mut u8) -> i32 {
+    let running = STATE.with_borrow_mut(|s| match s.processes.get_mut(&pid) {
+        Some(process) if process.polls > 0 => {
+            process.polls -= 1;
+            Some(true)
+        },
+        Some(_) => Some(false),
+        None => None,
+    });
+    match running {
+        None => FsResult::NotFound as i32,
+        Some(true) => {
+            unsafe { *exited = 0; }
+            FsResult::Success as i32
+        },
+        Some(false) => {
+            unsafe { *exited = 1; }
+            unsafe { ffi_wait(pid, status) }
+        },
+    }
+}
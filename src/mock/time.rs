@@ -0,0 +1,26 @@
+use core::cell::Cell;
+
+use crate::time::Duration;
+
+std::thread_local! {
+    static NOW_MS: Cell<u64> = const { Cell::new(0) };
+}
+
+pub unsafe fn ffi_get_time_ms() -> u64 {
+    NOW_MS.get()
+}
+
+// sleeping just moves the clock forward so tests never actually wait
+pub unsafe fn ffi_sleep_ms(ms: u64) {
+    NOW_MS.set(NOW_MS.get() + ms);
+}
+
+/// Sets the simulated clock to `ms` milliseconds.
+pub fn set_millis(ms: u64) {
+    NOW_MS.set(ms);
+}
+
+/// Moves the simulated clock forward by `dur`.
+pub fn advance(dur: Duration) {
+    NOW_MS.set(NOW_MS.get() + dur.as_millis() as u64);
+}
@@ -0,0 +1,245 @@
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+use core::ffi::c_char;
+use core::{fmt, ptr};
+
+use alloc::{collections::BTreeMap, ffi::CString, vec::Vec};
+use path::{Path, PathBuf};
+use unix_str::{UnixStr, UnixString};
+
+use crate::{env, fs::{self, path_to_cstring, FsResult}, io::{self, Error, ErrorKind}};
+
+#[cfg(all(feature = "proposed-abi", not(feature = "mock")))]
+unsafe extern "C" {
+    // starts `path` in `cwd` with a C style `argv` and `envp` of `KEY=VALUE` strings,
+    // the child gets `envp` back from `get_environ`. It fails with the same codes as
+    // opening `path` would
+    #[link_name = "spawn"]
+    pub unsafe fn ffi_spawn(path: *const c_char, argv: *const *const c_char, argc: u64, envp: *const *const c_char, envc: u64, cwd: *const c_char, pid: *mut u32) -> i32;
+    // blocks until `pid` exits
+    #[link_name = "wait"]
+    pub unsafe fn ffi_wait(pid: u32, status: *mut i32) -> i32;
+    // leaves `*exited` at 0 while `pid` is still running
+    #[link_name = "try_wait"]
+    pub unsafe fn ffi_try_wait(pid: u32, status: *mut i32, exited: *mut u8) -> i32;
+}
+
+#[cfg(feature = "mock")]
+pub use crate::mock::process::{ffi_spawn, ffi_wait, ffi_try_wait};
+
+#[cfg(not(feature = "proposed-abi"))]
+pub use crate::proposed::{ffi_spawn, ffi_wait, ffi_try_wait};
+
+/// A program to start along with its arguments, environment and directory.
+///
+/// The child gets this process' environment unless it's changed here, and its
+/// current directory unless [`current_dir`](Command::current_dir) is set. The
+/// directory also goes into the environment as `PWD`, which is where a Rust
+/// child's [`env::current_dir`] starts out.
+///
+/// ```ignore
+/// let status = Command::new("/bin/convert").arg("-o").arg("out.bmp").arg("in.png").status()?;
+/// if !status.success() {
+///     eprintln!("convert failed: {status}");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Command {
+    program: UnixString,
+    args: Vec<UnixString>,
+    // `None` removes a variable the child would otherwise inherit
+    env: BTreeMap<UnixString, Option<UnixString>>,
+    clear_env: bool,
+    cwd: Option<PathBuf>,
+}
+
+impl Command {
+    /// A command running `program`, a name without a `/` is looked up in the
+    /// directories listed in `PATH` and otherwise resolved like any other path.
+    pub fn new<S: AsRef<UnixStr>>(program: S) -> Self {
+        Self { program: program.as_ref().to_unix_string(), args: Vec::new(), env: BTreeMap::new(), clear_env: false, cwd: None }
+    }
+
+    pub fn arg<S: AsRef<UnixStr>>(&mut self, arg: S) -> &mut Self {
+        self.args.push(arg.as_ref().to_unix_string());
+        self
+    }
+
+    pub fn args<I: IntoIterator<Item = S>, S: AsRef<UnixStr>>(&mut self, args: I) -> &mut Self {
+        for arg in args {
+            self.arg(arg);
+        }
+        self
+    }
+
+    pub fn env<K: AsRef<UnixStr>, V: AsRef<UnixStr>>(&mut self, key: K, value: V) -> &mut Self {
+        self.env.insert(key.as_ref().to_unix_string(), Some(value.as_ref().to_unix_string()));
+        self
+    }
+
+    pub fn envs<I: IntoIterator<Item = (K, V)>, K: AsRef<UnixStr>, V: AsRef<UnixStr>>(&mut self, vars: I) -> &mut Self {
+        for (key, value) in vars {
+            self.env(key, value);
+        }
+        self
+    }
+
+    pub fn env_remove<K: AsRef<UnixStr>>(&mut self, key: K) -> &mut Self {
+        self.env.insert(key.as_ref().to_unix_string(), None);
+        self
+    }
+
+    /// Starts the child with only the variables set on this command.
+    pub fn env_clear(&mut self) -> &mut Self {
+        self.env.clear();
+        self.clear_env = true;
+        self
+    }
+
+    pub fn current_dir<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
+        self.cwd = Some(dir.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn get_program(&self) -> &UnixStr {
+        &self.program
+    }
+
+    pub fn get_args(&self) -> impl Iterator<Item = &UnixStr> {
+        self.args.iter().map(|arg| arg.as_ref())
+    }
+
+    pub fn get_current_dir(&self) -> Option<&Path> {
+        self.cwd.as_deref()
+    }
+
+    fn resolve_program(&self) -> io::Result<PathBuf> {
+        let program = Path::new(&self.program);
+        if self.program.as_bytes().contains(&b'/') {
+            return Ok(program.to_path_buf());
+        }
+        let search = env::var_os("PATH").unwrap_or_default();
+        for dir in search.as_bytes().split(|&b| b == b':').filter(|dir| !dir.is_empty()) {
+            let candidate = Path::new(UnixStr::from_bytes(dir)).join(program);
+            if fs::metadata(&candidate).is_ok_and(|meta| meta.is_file()) {
+                return Ok(candidate);
+            }
+        }
+        Ok(program.to_path_buf())
+    }
+
+    fn child_env(&self, dir: &Path) -> BTreeMap<UnixString, UnixString> {
+        let mut vars: BTreeMap<_, _> = match self.clear_env {
+            true => BTreeMap::new(),
+            false => env::vars_os().collect(),
+        };
+        for (key, value) in &self.env {
+            match value {
+                Some(value) => vars.insert(key.clone(), value.clone()),
+                None => vars.remove(key),
+            };
+        }
+        vars.insert(UnixString::from("PWD"), dir.as_unix_str().to_unix_string());
+        vars
+    }
+
+    /// Starts the program and returns without waiting for it.
+    pub fn spawn(&mut self) -> io::Result<Child> {
+        const NUL: Error = Error::const_new(ErrorKind::InvalidInput, "argument or variable contains a NUL");
+        let program = path_to_cstring(&self.resolve_program()?)?;
+        let dir = match &self.cwd {
+            Some(dir) => fs::absolute(dir)?,
+            None => env::current_dir()?,
+        };
+        let cwd = path_to_cstring(&dir)?;
+
+        // argv starts with the program as it was given, like a shell would pass it
+        let argv = core::iter::once(&self.program).chain(&self.args)
+            .map(|arg| CString::new(arg.as_bytes()).map_err(|_| NUL))
+            .collect::<io::Result<Vec<_>>>()?;
+        let envp = self.child_env(&dir).into_iter()
+            .map(|(key, value)| CString::new([key.as_bytes(), b"=", value.as_bytes()].concat()).map_err(|_| NUL))
+            .collect::<io::Result<Vec<_>>>()?;
+        let argv_ptrs: Vec<_> = argv.iter().map(|arg| arg.as_ptr()).chain([ptr::null()]).collect();
+        let envp_ptrs: Vec<_> = envp.iter().map(|var| var.as_ptr()).chain([ptr::null()]).collect();
+
+        let mut pid = 0;
+        unsafe {
+            FsResult::check(ffi_spawn(
+                program.as_ptr(),
+                argv_ptrs.as_ptr(), argv.len() as u64,
+                envp_ptrs.as_ptr(), envp.len() as u64,
+                cwd.as_ptr(),
+                &mut pid as _,
+            ))?;
+        }
+        Ok(Child { pid, status: None })
+    }
+
+    /// Starts the program and waits for it to exit.
+    pub fn status(&mut self) -> io::Result<ExitStatus> {
+        self.spawn()?.wait()
+    }
+}
+
+/// A process started by [`Command::spawn`].
+///
+/// Dropping it doesn't stop or wait for the process.
+#[derive(Debug)]
+pub struct Child {
+    pid: u32,
+    // RedactedOS forgets a process once it has been waited for
+    status: Option<ExitStatus>,
+}
+
+impl Child {
+    pub fn id(&self) -> u32 {
+        self.pid
+    }
+
+    /// Blocks until the child exits, later calls return the same status.
+    pub fn wait(&mut self) -> io::Result<ExitStatus> {
+        if let Some(status) = self.status {
+            return Ok(status);
+        }
+        let mut code = 0;
+        unsafe { FsResult::check(ffi_wait(self.pid, &mut code as _))?; }
+        self.status = Some(ExitStatus { code });
+        Ok(ExitStatus { code })
+    }
+
+    /// The exit status if the child has exited, `None` while it's still running.
+    pub fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        if self.status.is_some() {
+            return Ok(self.status);
+        }
+        let (mut code, mut exited) = (0, 0u8);
+        unsafe { FsResult::check(ffi_try_wait(self.pid, &mut code as _, &mut exited as _))?; }
+        if exited != 0 {
+            self.status = Some(ExitStatus { code });
+        }
+        Ok(self.status)
+    }
+}
+
+/// How a child process ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExitStatus {
+    code: i32,
+}
+
+impl ExitStatus {
+    /// The code the process exited with, RedactedOS has no signals so there always is one.
+    pub fn code(&self) -> Option<i32> {
+        Some(self.code)
+    }
+
+    pub fn success(&self) -> bool {
+        self.code == 0
+    }
+}
+
+impl fmt::Display for ExitStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "exit status: {}", self.code)
+    }
+}
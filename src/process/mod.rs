@@ -1,24 +1,91 @@
-use core::{convert::Infallible, fmt};
+use core::fmt;
 
-use crate::println;
+use alloc::{boxed::Box, vec::Vec};
 
+use crate::{io::{self, Error, ErrorKind, Write}, println, sync::global};
+
+mod command;
+pub use command::*;
+
+#[cfg(not(feature = "mock"))]
 unsafe extern "C" {
     #[link_name = "halt"]
     pub unsafe fn ffi_exit(exit_code: u32) -> !;
 }
 
-pub enum ExitCode {
-    SUCCESS = 0,
-    FAILURE = 1,
-}
+#[cfg(feature = "mock")]
+pub use crate::mock::process::ffi_exit;
+
+/// The code a process exits with, 0 for success and anything else for a failure
+/// whoever started the process can tell apart by value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExitCode(u8);
 
 impl ExitCode {
+    pub const SUCCESS: ExitCode = ExitCode(0);
+    pub const FAILURE: ExitCode = ExitCode(1);
+
     pub fn exit_process(self) -> ! {
-        unsafe { ffi_exit(self as u32); }
+        exit(self.to_i32())
+    }
+
+    pub fn to_i32(self) -> i32 {
+        self.0 as i32
     }
 }
 
-#[lang = "termination"]
+impl From<u8> for ExitCode {
+    fn from(code: u8) -> Self {
+        Self(code)
+    }
+}
+
+/// What [`abort`] exits with, the code a shell shows for `SIGABRT`.
+pub const ABORT_EXIT_CODE: i32 = 134;
+
+type ExitHook = Box<dyn FnOnce() + Send>;
+
+global! {
+    static EXIT_HOOKS: Vec<ExitHook> = Vec::new();
+}
+
+/// Registers `hook` to run before the process halts, whether `main` returned,
+/// [`exit`] was called or something panicked.
+///
+/// Hooks run in reverse order of registration, one registered while the hooks
+/// are running runs right after the current one. A hook that panics halts the
+/// process without running the ones left. `halt` doesn't run destructors, so
+/// this is the place to close files or save state.
+pub fn at_exit<F: FnOnce() + Send + 'static>(hook: F) -> io::Result<()> {
+    let mut hooks = EXIT_HOOKS.try_lock().ok_or(Error::const_new(ErrorKind::ResourceBusy, "exit hooks are being changed"))?;
+    hooks.push(Box::new(hook));
+    Ok(())
+}
+
+// runs the exit hooks and writes out partial lines left in the console buffers,
+// the lock is let go before each hook so hooks can register more
+pub(crate) fn run_exit_hooks() {
+    while let Some(hook) = EXIT_HOOKS.try_lock().and_then(|mut hooks| hooks.pop()) {
+        hook();
+    }
+    let _ = io::stdout().flush();
+    let _ = io::stderr().flush();
+}
+
+/// Runs the [`at_exit`] hooks and ends the process with `code`, which reaches
+/// RedactedOS unchanged.
+pub fn exit(code: i32) -> ! {
+    run_exit_hooks();
+    unsafe { ffi_exit(code as u32); }
+}
+
+/// Ends the process abnormally with [`ABORT_EXIT_CODE`], without running the
+/// [`at_exit`] hooks or flushing anything.
+pub fn abort() -> ! {
+    unsafe { ffi_exit(ABORT_EXIT_CODE as u32); }
+}
+
+#[cfg_attr(not(feature = "mock"), lang = "termination")]
 #[diagnostic::on_unimplemented(
     message = "`main` has invalid return type `{Self}`",
     label = "`main` can only return types that implement `{Self}`"
@@ -39,12 +106,6 @@ impl Termination for ! {
     }
 }
 
-impl Termination for Infallible {
-    fn report(self) -> ExitCode {
-        match self {}
-    }
-}
-
 impl Termination for ExitCode {
     fn report(self) -> ExitCode {
         self
@@ -61,4 +122,59 @@ impl<T: Termination, E: fmt::Debug> Termination for Result<T, E> {
             }
         }
     }
+}
+
+/// An error along with the code the process exits with when `main` returns it
+/// as `Result<T, ExitError<E>>`.
+///
+/// `?` wraps any `E` with [`ExitCode::FAILURE`], [`ExitError::new`] picks the code.
+/// It doesn't implement `Debug`, which keeps its [`Termination`] apart from the
+/// one for any other `Result`.
+///
+/// ```ignore
+/// fn main() -> Result<(), ExitError<io::Error>> {
+///     let config = fs::read("/cfg/app.ini").map_err(|err| ExitError::new(ExitCode::from(2), err))?;
+///     run(&config)?;
+///     Ok(())
+/// }
+/// ```
+pub struct ExitError<E> {
+    code: ExitCode,
+    error: E,
+}
+
+impl<E> ExitError<E> {
+    pub fn new(code: ExitCode, error: E) -> Self {
+        Self { code, error }
+    }
+
+    pub fn code(&self) -> ExitCode {
+        self.code
+    }
+
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E> From<E> for ExitError<E> {
+    fn from(error: E) -> Self {
+        Self::new(ExitCode::FAILURE, error)
+    }
+}
+
+impl<T: Termination, E: fmt::Debug> Termination for Result<T, ExitError<E>> {
+    fn report(self) -> ExitCode {
+        match self {
+            Ok(val) => val.report(),
+            Err(ExitError { code, error }) => {
+                println!("Error: {:#?}", error);
+                code
+            }
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,68 @@
+//! Stand-ins for the RedactedOS symbols this crate calls that the OS doesn't export yet.
+//!
+//! The real declarations are only linked with the `proposed-abi` feature. Without it
+//! these take their place and fail with [`UNSUPPORTED`], which turns into
+//! [`ErrorKind::Unsupported`](crate::io::ErrorKind::Unsupported), so an app built today
+//! still links and gets an error instead of an undefined symbol.
+#![allow(clippy::missing_safety_doc)]
+
+use core::ffi::c_char;
+
+use crate::fs::{FileDescriptor, OpenFlags, RawMetadata, UNSUPPORTED};
+
+pub unsafe fn ffi_fopen_mode(_path: *const c_char, _flags: OpenFlags, _descriptor: *mut FileDescriptor) -> i32 {
+    UNSUPPORTED
+}
+
+// nothing can be opened for writing, so nothing is ever written
+pub unsafe fn ffi_fwrite(_descriptor: *mut FileDescriptor, _buf: *const c_char, _size: u64) -> u64 {
+    0
+}
+
+pub unsafe fn ffi_fsync(_descriptor: *const FileDescriptor) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_dir_list(_path: *const c_char, _buf: *mut c_char, _size: u64, _offset: *mut u64) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_stat(_path: *const c_char, _metadata: *mut RawMetadata) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_fstat(_descriptor: *const FileDescriptor, _metadata: *mut RawMetadata) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_mkdir(_path: *const c_char) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_rmdir(_path: *const c_char) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_unlink(_path: *const c_char) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_rename(_from: *const c_char, _to: *const c_char) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_spawn(_path: *const c_char, _argv: *const *const c_char, _argc: u64, _envp: *const *const c_char, _envc: u64, _cwd: *const c_char, _pid: *mut u32) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_wait(_pid: u32, _status: *mut i32) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_try_wait(_pid: u32, _status: *mut i32, _exited: *mut u8) -> i32 {
+    UNSUPPORTED
+}
+
+pub unsafe fn ffi_get_environ() -> *const *const c_char {
+    core::ptr::null()
+}
@@ -4,6 +4,9 @@ pub struct Random<R: Read + 'static> {
     source: R,
 }
 
+/// # Safety
+///
+/// `/random` has to be RedactedOS's random device, nothing checks that it is.
 pub unsafe fn random_source() -> File {
     File::open("/random").unwrap()
 }
@@ -14,7 +17,16 @@ impl Random<File> {
     }
 }
 
+impl Default for Random<File> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<R: Read> Random<R> {
+    /// # Safety
+    ///
+    /// `source` has to yield unpredictable bytes, it is used as is.
     pub unsafe fn with_source(source: R) -> Self {
         Self { source }
     }
@@ -1,20 +1,55 @@
-use core::panic::PanicInfo;
+#[cfg(not(feature = "mock"))]
+use core::{fmt::Write as _, panic::PanicInfo, sync::atomic::{AtomicBool, Ordering}};
 
-use crate::{println, process::{self, Termination}};
+use crate::{env, process::{self, Termination}};
+#[cfg(not(feature = "mock"))]
+use crate::io::{self, ConsoleWriter, Write as _};
 
+/// What a panicking process exits with, same as std.
+#[cfg(not(feature = "mock"))]
+const PANIC_EXIT_CODE: i32 = 101;
+
+#[cfg(not(feature = "mock"))]
+static PANICKING: AtomicBool = AtomicBool::new(false);
+
+#[cfg(not(feature = "mock"))]
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
-    println!("{}", info);
-    process::ExitCode::FAILURE.exit_process()
+    let first = !PANICKING.swap(true, Ordering::Relaxed);
+    // whatever was printed before the panic comes before its message
+    if first {
+        let _ = io::stdout().flush();
+        let _ = io::stderr().flush();
+    }
+    // stays off the heap, the panic may well be an allocation failure
+    let mut out = ConsoleWriter::<256>::new();
+    let _ = writeln!(out, "{info}");
+    out.finish();
+    // a panic in an exit hook halts right away instead of starting the hooks over
+    if first {
+        process::exit(PANIC_EXIT_CODE)
+    }
+    unsafe { process::ffi_exit(PANIC_EXIT_CODE as u32) }
 }
 
-#[lang = "start"]
-fn lang_start<T: Termination + 'static>(
+#[cfg_attr(not(feature = "mock"), lang = "start")]
+pub(crate) fn lang_start<T: Termination + 'static>(
     main: fn() -> T,
-    _argc: isize,
-    _argv: *const *const u8,
+    argc: isize,
+    argv: *const *const u8,
     _: u8
 ) -> isize {
-    // TODO: argc, argv though i don't think RedactedOS has a way to provide those yet
-    main().report() as isize
-}
\ No newline at end of file
+    unsafe { init(argc, argv); }
+    let code = main().report();
+    process::run_exit_hooks();
+    code.to_i32() as isize
+}
+
+// what a process gets besides `main`, the mock's child processes start the same way
+pub(crate) unsafe fn init(argc: isize, argv: *const *const u8) {
+    unsafe {
+        // RedactedOS doesn't pass any yet, `env` falls back to its arguments file then
+        env::init_args(argc, argv);
+        env::init_env(env::ffi_get_environ() as _);
+    }
+}
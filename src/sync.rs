@@ -0,0 +1,119 @@
+use core::{cell::UnsafeCell, ops::{Deref, DerefMut}, sync::atomic::{AtomicBool, Ordering}};
+
+// RedactedOS runs a process on a single thread, so the only way to find this
+// locked is reentrancy and waiting would never end
+pub(crate) struct TryLock<T> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: Send> Sync for TryLock<T> {}
+
+impl<T> TryLock<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self { locked: AtomicBool::new(false), value: UnsafeCell::new(value) }
+    }
+
+    pub(crate) fn try_lock(&self) -> Option<TryLockGuard<'_, T>> {
+        if self.locked.swap(true, Ordering::Acquire) {
+            None
+        } else {
+            Some(TryLockGuard { lock: self })
+        }
+    }
+}
+
+pub(crate) struct TryLockGuard<'a, T> {
+    lock: &'a TryLock<T>,
+}
+
+impl<T> Deref for TryLockGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T> DerefMut for TryLockGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T> Drop for TryLockGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.locked.store(false, Ordering::Release);
+    }
+}
+
+// Declares a static holding crate state that RedactedOS would keep per process.
+// With `mock` every test thread gets its own, like the simulated OS state, and
+// `mock::reset` starts them over
+macro_rules! global {
+    ($vis:vis static $name:ident: $ty:ty = $init:expr;) => {
+        #[cfg(not(feature = "mock"))]
+        $vis static $name: $crate::sync::TryLock<$ty> = $crate::sync::TryLock::new($init);
+
+        #[cfg(feature = "mock")]
+        $vis static $name: $crate::sync::PerThread<$ty> = {
+            fn init() -> $ty {
+                $init
+            }
+            std::thread_local! {
+                static SLOT: $crate::sync::Slot<$ty> = const { core::cell::Cell::new(None) };
+            }
+            $crate::sync::PerThread::new(&SLOT, init)
+        };
+    };
+}
+
+pub(crate) use global;
+
+#[cfg(feature = "mock")]
+pub(crate) type Global<T> = PerThread<T>;
+#[cfg(not(feature = "mock"))]
+pub(crate) type Global<T> = TryLock<T>;
+
+// the reset count the value was made at and the value, leaked so guards can be 'static
+#[cfg(feature = "mock")]
+pub(crate) type Slot<T> = core::cell::Cell<Option<(u64, &'static TryLock<T>)>>;
+
+#[cfg(feature = "mock")]
+std::thread_local! {
+    static RESETS: core::cell::Cell<u64> = const { core::cell::Cell::new(0) };
+}
+
+#[cfg(feature = "mock")]
+pub(crate) fn reset_globals() {
+    RESETS.with(|resets| resets.set(resets.get() + 1));
+}
+
+#[cfg(feature = "mock")]
+pub(crate) struct PerThread<T: 'static> {
+    slot: &'static std::thread::LocalKey<Slot<T>>,
+    init: fn() -> T,
+}
+
+#[cfg(feature = "mock")]
+impl<T> PerThread<T> {
+    pub(crate) const fn new(slot: &'static std::thread::LocalKey<Slot<T>>, init: fn() -> T) -> Self {
+        Self { slot, init }
+    }
+
+    fn get(&self) -> &'static TryLock<T> {
+        let resets = RESETS.with(core::cell::Cell::get);
+        self.slot.with(|slot| match slot.get() {
+            Some((at, lock)) if at == resets => lock,
+            _ => {
+                let lock: &'static TryLock<T> = std::boxed::Box::leak(std::boxed::Box::new(TryLock::new((self.init)())));
+                slot.set(Some((resets, lock)));
+                lock
+            },
+        })
+    }
+
+    pub(crate) fn try_lock(&self) -> Option<TryLockGuard<'static, T>> {
+        self.get().try_lock()
+    }
+}
@@ -1,10 +1,14 @@
 use crate::time::Duration;
 
+#[cfg(not(feature = "mock"))]
 unsafe extern "C" {
     #[link_name = "sleep"]
     pub unsafe fn ffi_sleep_ms(ms: u64);
 }
 
+#[cfg(feature = "mock")]
+pub use crate::mock::time::ffi_sleep_ms;
+
 pub fn sleep(dur: Duration) {
     let ms = dur.as_millis() as u64;
     unsafe {
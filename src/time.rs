@@ -1,11 +1,15 @@
 use core::ops::{Add, AddAssign, Sub, SubAssign};
 pub use core::time::Duration;
 
+#[cfg(not(feature = "mock"))]
 unsafe extern "C" {
     #[link_name = "get_time"]
     pub unsafe fn ffi_get_time_ms() -> u64;
 }
 
+#[cfg(feature = "mock")]
+pub use crate::mock::time::ffi_get_time_ms;
+
 #[derive(Debug, Clone, Copy)]
 pub struct SystemTime(u64);
 
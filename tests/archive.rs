@@ -0,0 +1,135 @@
+mod common;
+
+use common::tar_entry;
+use redacted_shared_rs::{archive::{TarArchive, ZipArchive}, fs::File, io::{Cursor, ErrorKind, Read}, mock, path::Path};
+
+const ASSETS_ZIP: &[u8] = include_bytes!("data/assets.zip");
+
+fn lines() -> String {
+    (0..3000).map(|i| format!("line {} of the asset list\n", i % 97)).collect()
+}
+
+#[test]
+fn tar_entries() {
+    let mut archive = tar_entry("docs/", b'5', b"");
+    archive.extend(tar_entry("docs/a.txt", b'0', b"first"));
+    archive.extend(tar_entry("docs/link", b'2', b""));
+    archive.extend(tar_entry("big.bin", b'0', &[7; 1000]));
+    archive.extend([0; 1024]);
+    let mut tar = TarArchive::new(Cursor::new(archive)).unwrap();
+
+    let listed: Vec<_> = tar.entries().map(|entry| (entry.path().to_path_buf(), entry.size(), entry.is_dir())).collect();
+    assert_eq!(listed, [
+        (Path::new("docs").to_path_buf(), 0, true),
+        (Path::new("docs/a.txt").to_path_buf(), 5, false),
+        (Path::new("big.bin").to_path_buf(), 1000, false),
+    ]);
+    // paths compare by components, the stored name really lost its slash
+    assert_eq!(tar.entries().next().unwrap().path().as_unix_str().as_bytes(), b"docs");
+
+    let mut text = String::new();
+    tar.by_name("docs/./a.txt").unwrap().read_to_string(&mut text).unwrap();
+    assert_eq!(text, "first");
+    let mut data = Vec::new();
+    let mut reader = tar.by_index(2).unwrap();
+    assert_eq!(reader.entry().path(), Path::new("big.bin"));
+    reader.read_to_end(&mut data).unwrap();
+    assert_eq!(data, [7; 1000]);
+    assert_eq!(tar.by_name("docs").unwrap_err().kind(), ErrorKind::IsADirectory);
+    assert_eq!(tar.by_name("docs/link").unwrap_err().kind(), ErrorKind::NotFound);
+
+    let mut truncated = tar_entry("a", b'0', &[1; 600]);
+    truncated.truncate(700);
+    assert_eq!(TarArchive::new(Cursor::new(truncated)).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(TarArchive::new(Cursor::new(vec![1; 512])).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn tar_dot_prefix_and_long_names() {
+    let long = format!("{}/deep.txt", "dir".repeat(60));
+    let mut archive = tar_entry("./", b'5', b"");
+    archive.extend(tar_entry("./foo", b'0', b"foo"));
+    archive.extend(tar_entry("././@LongLink", b'L', format!("{long}\0").as_bytes()));
+    archive.extend(tar_entry(&long[..100], b'0', b"gnu"));
+    // a pax record counts its own length digits
+    let record = format!(" path=pax/{long}\n");
+    let record = format!("{}{record}", record.len() + 3);
+    archive.extend(tar_entry("PaxHeader", b'x', record.as_bytes()));
+    archive.extend(tar_entry("short", b'0', b"pax"));
+    let mut tar = TarArchive::new(Cursor::new(archive)).unwrap();
+
+    let names: Vec<_> = tar.entries().map(|entry| entry.path().to_path_buf()).collect();
+    assert_eq!(names, [Path::new("foo").to_path_buf(), Path::new(&long).to_path_buf(), Path::new(&format!("pax/{long}")).to_path_buf()]);
+    let mut text = String::new();
+    tar.by_name("foo").unwrap().read_to_string(&mut text).unwrap();
+    assert_eq!(text, "foo");
+    text.clear();
+    tar.by_name(&long).unwrap().read_to_string(&mut text).unwrap();
+    assert_eq!(text, "gnu");
+
+    let mut huge = tar_entry("././@LongLink", b'L', &vec![b'a'; 100_000]);
+    huge.extend(tar_entry("a", b'0', b""));
+    assert_eq!(TarArchive::new(Cursor::new(huge)).unwrap_err().kind(), ErrorKind::Unsupported);
+}
+
+#[test]
+fn zip_entries() {
+    let mut zip = ZipArchive::new(Cursor::new(ASSETS_ZIP)).unwrap();
+    let names: Vec<_> = zip.entries().map(|entry| entry.path().to_path_buf()).collect();
+    assert_eq!(names, ["readme.txt", "data", "data/short.txt", "data/lines.txt", "data/raw.bin"].map(|name| Path::new(name).to_path_buf()));
+    assert!(zip.entries().nth(1).unwrap().is_dir());
+    assert_eq!(zip.entries().nth(1).unwrap().path().as_unix_str().as_bytes(), b"data");
+    let lines_entry = zip.entries().nth(3).unwrap();
+    assert_eq!(lines_entry.size(), lines().len() as u64);
+    assert!(lines_entry.compressed_size() < lines_entry.size());
+
+    let mut text = String::new();
+    zip.by_name("readme.txt").unwrap().read_to_string(&mut text).unwrap();
+    assert_eq!(text, "stored entry");
+    text.clear();
+    zip.by_name("data/short.txt").unwrap().read_to_string(&mut text).unwrap();
+    assert_eq!(text, "hello hello hello deflate");
+    text.clear();
+    zip.by_name("data/lines.txt").unwrap().read_to_string(&mut text).unwrap();
+    assert_eq!(text, lines());
+    let mut data = Vec::new();
+    zip.by_name("data/raw.bin").unwrap().read_to_end(&mut data).unwrap();
+    assert_eq!(data, [(0..=255).collect::<Vec<u8>>(), (0..=255).collect()].concat());
+
+    // reading in small pieces goes through the same decoder states
+    let mut reader = zip.by_name("data/lines.txt").unwrap();
+    let mut piece = [0; 7];
+    let mut total = 0;
+    loop {
+        match reader.read(&mut piece).unwrap() {
+            0 => break,
+            n => total += n,
+        }
+    }
+    assert_eq!(total, lines().len());
+}
+
+#[test]
+fn zip_from_file() {
+    mock::fs::add_file("/apps/assets.zip", ASSETS_ZIP);
+    let mut zip = ZipArchive::new(File::open("/apps/assets.zip").unwrap()).unwrap();
+    let mut text = String::new();
+    zip.by_name("data/lines.txt").unwrap().read_to_string(&mut text).unwrap();
+    assert_eq!(text, lines());
+}
+
+#[test]
+fn zip_errors() {
+    assert_eq!(ZipArchive::new(Cursor::new(b"not a zip")).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // flip a byte inside the stored entry's data, the checksum catches it
+    let mut corrupt = ASSETS_ZIP.to_vec();
+    let at = corrupt.windows(12).position(|w| w == b"stored entry").unwrap();
+    corrupt[at] = b'S';
+    let mut zip = ZipArchive::new(Cursor::new(corrupt)).unwrap();
+    let mut text = String::new();
+    assert_eq!(zip.by_name("readme.txt").unwrap().read_to_string(&mut text).unwrap_err().kind(), ErrorKind::InvalidData);
+    let mut exact = [0; 12];
+    assert_eq!(zip.by_name("readme.txt").unwrap().read_exact(&mut exact).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(zip.by_name("missing").unwrap_err().kind(), ErrorKind::NotFound);
+}
@@ -0,0 +1,20 @@
+//! Helpers shared by the integration tests.
+
+// a ustar header plus the padded data for one entry
+pub fn tar_entry(name: &str, kind: u8, data: &[u8]) -> Vec<u8> {
+    let mut header = [0u8; 512];
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[100..107].copy_from_slice(b"0000644");
+    header[124..135].copy_from_slice(format!("{:011o}", data.len()).as_bytes());
+    header[156] = kind;
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    header[148..156].fill(b' ');
+    let sum: u32 = header.iter().map(|&b| b as u32).sum();
+    header[148..155].copy_from_slice(format!("{sum:06o}\0").as_bytes());
+
+    let mut entry = header.to_vec();
+    entry.extend_from_slice(data);
+    entry.resize(512 + data.len().div_ceil(512) * 512, 0);
+    entry
+}
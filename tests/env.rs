@@ -0,0 +1,72 @@
+use std::ffi::CString;
+
+use redacted_shared_rs::{env::{self, VarError}, mock, unix_str::UnixString};
+
+// the arguments are set once per process, so the ways of setting them are checked in order
+#[test]
+fn args_from_argv_and_file() {
+    let owned = [CString::new("/bin/tool").unwrap(), CString::new("--level").unwrap(), CString::new(vec![b'x', 0xFF]).unwrap()];
+    let argv: Vec<*const u8> = owned.iter().map(|arg| arg.as_ptr() as *const u8).collect();
+    unsafe { env::init_args(2, argv.as_ptr()); }
+    assert_eq!(env::args().collect::<Vec<_>>(), ["/bin/tool", "--level"]);
+    assert_eq!(env::args().len(), 2);
+    assert_eq!(env::args().next_back().unwrap(), "--level");
+
+    unsafe { env::init_args(3, argv.as_ptr()); }
+    assert_eq!(env::args_os().next_back().unwrap(), UnixString::from_vec(vec![b'x', 0xFF]));
+    assert!(std::panic::catch_unwind(|| env::args().count()).is_err());
+
+    // no arguments and no file
+    unsafe { env::init_args(0, std::ptr::null()); }
+    assert_eq!(env::args().len(), 0);
+
+    unsafe { env::init_args(0, std::ptr::null()); }
+    mock::fs::add_file(env::ARGS_FILE, "/bin/launched\nwith space\n\n--flag\n");
+    assert_eq!(env::args().collect::<Vec<_>>(), ["/bin/launched", "with space", "", "--flag"]);
+    // taken by this process and kept
+    assert!(redacted_shared_rs::fs::metadata(env::ARGS_FILE).is_err());
+    assert_eq!(env::args().len(), 4);
+
+    // a file that can't be removed is still used, by every process that asks
+    unsafe { env::init_args(0, std::ptr::null()); }
+    mock::fs::set_proposed_abi(false);
+    mock::fs::add_file(env::ARGS_FILE, "/bin/kept\n");
+    assert_eq!(env::args().collect::<Vec<_>>(), ["/bin/kept"]);
+    assert_eq!(mock::fs::contents(env::ARGS_FILE).unwrap(), b"/bin/kept\n");
+    mock::fs::set_proposed_abi(true);
+}
+
+#[test]
+fn environment_variables() {
+    assert_eq!(env::var("ENV_TEST_MISSING"), Err(VarError::NotPresent));
+    env::set_var("ENV_TEST_HOME", "/home/user");
+    env::set_var("ENV_TEST_LANG", "en");
+    assert_eq!(env::var("ENV_TEST_HOME").unwrap(), "/home/user");
+    env::set_var("ENV_TEST_HOME", "/root");
+    assert_eq!(env::var_os("ENV_TEST_HOME").unwrap(), "/root");
+
+    let bytes = UnixString::from_vec(vec![0xC0]);
+    env::set_var("ENV_TEST_BYTES", &bytes);
+    assert_eq!(env::var("ENV_TEST_BYTES"), Err(VarError::NotUnicode(bytes)));
+    assert!(std::panic::catch_unwind(|| env::vars().count()).is_err());
+    assert!(env::vars_os().any(|(key, _)| key == "ENV_TEST_BYTES"));
+    env::remove_var("ENV_TEST_BYTES");
+
+    let ours: Vec<_> = env::vars().filter(|(key, _)| key.starts_with("ENV_TEST_")).collect();
+    assert_eq!(ours, [("ENV_TEST_HOME".into(), "/root".into()), ("ENV_TEST_LANG".into(), "en".into())]);
+    env::remove_var("ENV_TEST_HOME");
+    assert_eq!(env::var("ENV_TEST_HOME"), Err(VarError::NotPresent));
+
+    assert!(std::panic::catch_unwind(|| env::set_var("A=B", "c")).is_err());
+    assert!(std::panic::catch_unwind(|| env::set_var("", "c")).is_err());
+}
+
+#[test]
+fn reset_starts_crate_state_over() {
+    env::set_var("ENV_TEST_RESET", "set");
+    mock::fs::add_dir("/reset");
+    env::set_current_dir("/reset").unwrap();
+    mock::reset();
+    assert_eq!(env::var("ENV_TEST_RESET"), Err(VarError::NotPresent));
+    assert_eq!(env::current_dir().unwrap(), redacted_shared_rs::path::Path::new("/"));
+}
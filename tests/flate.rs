@@ -0,0 +1,70 @@
+use redacted_shared_rs::{flate::{DeflateDecoder, GzDecoder, ZlibDecoder}, fs::File, io::{ErrorKind, Read}, mock};
+
+const LINES_DEFLATE: &[u8] = include_bytes!("data/lines.deflate");
+const LINES_ZLIB: &[u8] = include_bytes!("data/lines.zlib");
+const LINES_GZ: &[u8] = include_bytes!("data/lines.txt.gz");
+
+fn lines() -> String {
+    (0..3000).map(|i| format!("line {} of the asset list\n", i % 97)).collect()
+}
+
+fn decode<R: Read>(mut reader: R) -> Result<String, ErrorKind> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text).map_err(|err| err.kind())?;
+    Ok(text)
+}
+
+#[test]
+fn decode_formats() {
+    assert_eq!(decode(DeflateDecoder::new(LINES_DEFLATE)).unwrap(), lines());
+    assert_eq!(decode(ZlibDecoder::new(LINES_ZLIB)).unwrap(), lines());
+    assert_eq!(decode(GzDecoder::new(LINES_GZ)).unwrap(), lines());
+
+    // `hi` without a file name, and a stored block
+    let gz: &[u8] = &[31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 203, 200, 4, 0, 172, 42, 147, 216, 2, 0, 0, 0];
+    assert_eq!(decode(GzDecoder::new(gz)).unwrap(), "hi");
+    let stored: &[u8] = &[1, 3, 0, 252, 255, b'a', b'b', b'c'];
+    assert_eq!(decode(DeflateDecoder::new(stored)).unwrap(), "abc");
+    assert_eq!(decode(DeflateDecoder::new(&[3, 0][..])).unwrap(), "");
+}
+
+#[test]
+fn decode_from_file() {
+    mock::fs::add_file("/assets/lines.txt.gz", LINES_GZ);
+    let mut gz = GzDecoder::new(File::open("/assets/lines.txt.gz").unwrap());
+    let mut piece = [0; 13];
+    let mut text = Vec::new();
+    loop {
+        match gz.read(&mut piece).unwrap() {
+            0 => break,
+            n => text.extend_from_slice(&piece[..n]),
+        }
+    }
+    assert_eq!(text, lines().as_bytes());
+    assert_eq!(gz.read(&mut piece).unwrap(), 0);
+}
+
+#[test]
+fn decode_errors() {
+    let mut zlib = LINES_ZLIB.to_vec();
+    *zlib.last_mut().unwrap() ^= 1;
+    assert_eq!(decode(ZlibDecoder::new(&zlib[..])), Err(ErrorKind::InvalidData));
+    assert_eq!(decode(ZlibDecoder::new(&[0x78, 0x00][..])), Err(ErrorKind::InvalidData));
+    assert_eq!(decode(ZlibDecoder::new(&[0x78, 0xBB, 0, 0, 0, 1][..])), Err(ErrorKind::Unsupported));
+
+    let mut gz = LINES_GZ.to_vec();
+    let len = gz.len();
+    gz[len - 8] ^= 1;
+    assert_eq!(decode(GzDecoder::new(&gz[..])), Err(ErrorKind::InvalidData));
+    gz[len - 8] ^= 1;
+    gz[len - 1] ^= 1;
+    assert_eq!(decode(GzDecoder::new(&gz[..])), Err(ErrorKind::InvalidData));
+    assert_eq!(decode(GzDecoder::new(&b"plain text"[..])), Err(ErrorKind::InvalidData));
+
+    assert_eq!(decode(DeflateDecoder::new(&LINES_DEFLATE[..100])), Err(ErrorKind::UnexpectedEof));
+    assert_eq!(decode(GzDecoder::new(&LINES_GZ[..LINES_GZ.len() - 4])), Err(ErrorKind::UnexpectedEof));
+    // block type 3 doesn't exist
+    assert_eq!(decode(DeflateDecoder::new(&[0x07][..])), Err(ErrorKind::InvalidData));
+    // stored block whose length and complement disagree
+    assert_eq!(decode(DeflateDecoder::new(&[1, 3, 0, 0, 0][..])), Err(ErrorKind::InvalidData));
+}
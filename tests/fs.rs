@@ -0,0 +1,612 @@
+mod common;
+
+use common::tar_entry;
+use redacted_shared_rs::{fs::{self, File, FsResult, OpenOptions}, io::{self, BorrowedBuf, BufRead, BufReader, ErrorKind, IoSliceMut, Read, Seek, SeekFrom, Write}, mock, path::Path, random::Random};
+
+#[test]
+fn open_missing_file() {
+    let err = File::open("/missing").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert_eq!(err.raw_os_error(), Some(FsResult::NotFound as i32));
+    assert_eq!(err.to_string(), "no such file or directory (os error 1)");
+}
+
+#[test]
+fn fs_result_conversions() {
+    assert!(io::Result::from(FsResult::Success).is_ok());
+    let err = io::Error::from(FsResult::DriverError);
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(err.raw_os_error(), Some(2));
+}
+
+#[test]
+fn raw_open_codes() {
+    assert_eq!(FsResult::try_from(2), Ok(FsResult::DriverError));
+    assert_eq!(FsResult::try_from(99), Err(99));
+
+    mock::fs::add_failing("/driver", FsResult::DriverError as i32);
+    let err = File::open("/driver").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(err.raw_os_error(), Some(2));
+
+    mock::fs::add_failing("/weird", 42);
+    let err = File::open("/weird").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Other);
+    assert_eq!(err.raw_os_error(), Some(42));
+    assert_eq!(err.to_string(), "unknown error (os error 42)");
+    assert_eq!(mock::fs::open_count(), 0);
+}
+
+#[test]
+fn read_and_seek() {
+    mock::fs::add_file("/data.txt", "hello world");
+    let mut file = File::open("/data.txt").unwrap();
+    assert_eq!(file.size(), 11);
+
+    let mut buf = [0u8; 5];
+    file.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+
+    assert_eq!(file.seek(SeekFrom::End(-5)).unwrap(), 6);
+    let mut rest = String::new();
+    file.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "world");
+}
+
+#[test]
+fn read_into_uninit_and_vectored() {
+    mock::fs::add_file("/asset.bin", "abcdefgh");
+    let mut file = File::open("/asset.bin").unwrap();
+
+    let mut storage = [core::mem::MaybeUninit::<u8>::uninit(); 16];
+    let mut buf = BorrowedBuf::from(&mut storage[..]);
+    file.read_buf(buf.unfilled()).unwrap();
+    assert_eq!(buf.filled(), b"abcdefgh");
+    // fread wrote straight into the buffer, nothing was zeroed beforehand
+    assert_eq!(buf.init_len(), 8);
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let (mut a, mut b, mut c) = ([0u8; 3], [0u8; 0], [0u8; 8]);
+    let mut bufs = [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b), IoSliceMut::new(&mut c)];
+    assert_eq!(file.read_vectored(&mut bufs).unwrap(), 8);
+    assert_eq!(&a, b"abc");
+    assert_eq!(&c[..5], b"defgh");
+
+    file.seek(SeekFrom::Start(2)).unwrap();
+    let mut data = Vec::with_capacity(64);
+    assert_eq!(file.read_to_end(&mut data).unwrap(), 6);
+    assert_eq!(data, b"cdefgh");
+}
+
+#[test]
+fn drop_closes() {
+    mock::fs::add_file("/a", "a");
+    let file = File::open("/a").unwrap();
+    assert_eq!(mock::fs::open_count(), 1);
+    drop(file);
+    assert_eq!(mock::fs::open_count(), 0);
+
+    File::open("/a").unwrap().close().unwrap();
+    assert_eq!(mock::fs::open_count(), 0);
+    assert_eq!(mock::fs::bad_close_count(), 0);
+}
+
+#[test]
+fn clones_share_descriptor() {
+    mock::fs::add_file("/shared", "0123456789");
+    let mut file = File::open("/shared").unwrap();
+    let mut buf = [0u8; 4];
+    file.read_exact(&mut buf).unwrap();
+
+    let mut clone = file.try_clone().unwrap();
+    assert_eq!(mock::fs::open_count(), 1);
+    clone.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"4567");
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"0123");
+
+    file.close().unwrap();
+    assert_eq!(mock::fs::open_count(), 1);
+    clone.read_exact(&mut buf[..2]).unwrap();
+    assert_eq!(&buf[..2], b"89");
+    drop(clone);
+    assert_eq!(mock::fs::open_count(), 0);
+    assert_eq!(mock::fs::bad_close_count(), 0);
+}
+
+#[test]
+fn clones_see_each_others_writes() {
+    let mut file = File::create("/grow.txt").unwrap();
+    let mut clone = file.try_clone().unwrap();
+    file.write_all(b"hello").unwrap();
+    assert_eq!(clone.size(), 5);
+    assert_eq!(clone.seek(SeekFrom::End(0)).unwrap(), 5);
+    clone.write_all(b" world").unwrap();
+    assert_eq!(file.seek(SeekFrom::End(-5)).unwrap(), 6);
+
+    let syncs = mock::fs::sync_count();
+    file.close().unwrap();
+    clone.close().unwrap();
+    assert_eq!(mock::fs::sync_count() - syncs, 2);
+    assert_eq!(mock::fs::open_count(), 0);
+    assert_eq!(mock::fs::contents("/grow.txt").unwrap(), b"hello world");
+}
+
+#[test]
+fn random_reads_source() {
+    mock::fs::add_stream("/random", [0u8, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12]);
+    let mut random = Random::new();
+    assert_eq!(random.next_u32().unwrap(), u32::from_le_bytes([0, 1, 2, 3]));
+}
+
+#[test]
+fn buffered_lines() {
+    mock::fs::add_file("/config.ini", "a=1\r\nb=2\n\nc=3");
+    let reader = BufReader::with_capacity(4, File::open("/config.ini").unwrap());
+    let lines: Vec<String> = reader.lines().map(Result::unwrap).collect();
+    assert_eq!(lines, ["a=1", "b=2", "", "c=3"]);
+}
+
+#[test]
+fn buffered_seek() {
+    mock::fs::add_file("/data.bin", "0123456789");
+    let mut reader = BufReader::with_capacity(4, File::open("/data.bin").unwrap());
+    let mut buf = [0u8; 2];
+    reader.read_exact(&mut buf).unwrap();
+    assert_eq!(reader.buffer(), b"23");
+    assert_eq!(reader.stream_position().unwrap(), 2);
+
+    reader.seek_relative(1).unwrap();
+    assert_eq!(reader.buffer(), b"3");
+    assert_eq!(reader.seek(SeekFrom::Current(2)).unwrap(), 5);
+    let mut split = reader.split(b'7');
+    assert_eq!(split.next().unwrap().unwrap(), b"56");
+    assert_eq!(split.next().unwrap().unwrap(), b"89");
+    assert!(split.next().is_none());
+}
+
+#[test]
+fn create_write_and_append() {
+    let mut file = File::create("/save.dat").unwrap();
+    file.write_all(b"level 1").unwrap();
+    file.flush().unwrap();
+    file.sync_all().unwrap();
+    assert_eq!(mock::fs::sync_count(), 1);
+    drop(file);
+    assert_eq!(mock::fs::contents("/save.dat").unwrap(), b"level 1");
+
+    let mut file = OpenOptions::new().write(true).open("/save.dat").unwrap();
+    file.write_all(b"LEVEL").unwrap();
+    drop(file);
+    assert_eq!(mock::fs::contents("/save.dat").unwrap(), b"LEVEL 1");
+
+    let mut log = OpenOptions::new().append(true).create(true).open("/log.txt").unwrap();
+    log.write_all(b"a\n").unwrap();
+    log.seek(SeekFrom::Start(0)).unwrap();
+    log.write_all(b"b\n").unwrap();
+    drop(log);
+    assert_eq!(mock::fs::contents("/log.txt").unwrap(), b"a\nb\n");
+
+    let mut file = File::create("/save.dat").unwrap();
+    assert_eq!(file.size(), 0);
+    file.write_all(b"new").unwrap();
+    drop(file);
+    assert_eq!(mock::fs::contents("/save.dat").unwrap(), b"new");
+}
+
+#[test]
+fn open_options_errors() {
+    mock::fs::add_file("/exists", "x");
+    let err = OpenOptions::new().write(true).create_new(true).open("/exists").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::AlreadyExists);
+    assert_eq!(OpenOptions::new().open("/exists").unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(OpenOptions::new().read(true).truncate(true).open("/exists").unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(OpenOptions::new().write(true).open("/missing").unwrap_err().kind(), ErrorKind::NotFound);
+
+    let mut file = File::open("/exists").unwrap();
+    assert_eq!(file.write(b"y").unwrap_err().kind(), ErrorKind::PermissionDenied);
+
+    mock::fs::set_readonly(true);
+    assert_eq!(File::create("/new").unwrap_err().kind(), ErrorKind::ReadOnlyFilesystem);
+    assert!(File::open("/exists").is_ok());
+}
+
+#[test]
+fn read_whole_files() {
+    let data: Vec<u8> = (0..100_000u32).map(|i| i as u8).collect();
+    mock::fs::add_file("/big.bin", data.clone());
+    let reads = mock::fs::read_count();
+    assert_eq!(fs::read("/big.bin").unwrap(), data);
+    assert_eq!(mock::fs::read_count() - reads, 1);
+
+    mock::fs::set_read_limit(Some(4096));
+    let mut file = File::open("/big.bin").unwrap();
+    let mut read = vec![1, 2, 3];
+    assert_eq!(file.read_to_end(&mut read).unwrap(), data.len());
+    assert_eq!(read[..3], [1, 2, 3]);
+    assert_eq!(read[3..], data);
+    mock::fs::set_read_limit(None);
+
+    fs::write("/out.txt", "written").unwrap();
+    assert_eq!(fs::read_to_string("/out.txt").unwrap(), "written");
+
+    mock::fs::add_file("/bad.txt", [0xff, 0xfe]);
+    assert_eq!(fs::read_to_string("/bad.txt").unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn list_directory() {
+    mock::fs::add_file("/assets/b.png", "b");
+    mock::fs::add_file("/assets/a.png", "a");
+    mock::fs::add_file("/assets/music/theme.ogg", "m");
+    mock::fs::add_stream("/assets/live", "");
+    mock::fs::add_dir("/assets/empty");
+
+    let mut entries: Vec<_> = fs::read_dir("/assets").unwrap().map(|entry| entry.unwrap()).collect();
+    entries.sort_by_key(|entry| entry.path());
+    let names: Vec<_> = entries.iter().map(|entry| entry.file_name().into_string().unwrap()).collect();
+    assert_eq!(names, ["a.png", "b.png", "empty", "live", "music"]);
+    assert_eq!(entries[0].path(), Path::new("/assets/a.png"));
+    assert!(entries[0].file_type().unwrap().is_file());
+    assert!(entries[2].file_type().unwrap().is_dir());
+    assert!(entries[3].file_type().unwrap().is_stream());
+    assert!(entries[4].file_type().unwrap().is_dir());
+
+    assert_eq!(fs::read_dir("/assets/empty").unwrap().count(), 0);
+    assert_eq!(fs::read_dir("/nowhere").unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(fs::read_dir("/assets/a.png").unwrap_err().kind(), ErrorKind::NotADirectory);
+    assert_eq!(File::open("/assets/music").unwrap_err().kind(), ErrorKind::IsADirectory);
+}
+
+#[test]
+fn list_large_directory() {
+    for i in 0..1000 {
+        mock::fs::add_file(&format!("/many/file_{i:04}"), "");
+    }
+    let entries: Vec<_> = fs::read_dir("/many/").unwrap().collect::<io::Result<_>>().unwrap();
+    assert_eq!(entries.len(), 1000);
+    assert_eq!(entries[999].file_name().into_string().unwrap(), "file_0999");
+
+    let long = "n".repeat(10_000);
+    mock::fs::add_file("/long/a", "");
+    mock::fs::add_file(&format!("/long/{long}"), "");
+    mock::fs::add_file("/long/z", "");
+    let names: Vec<_> = fs::read_dir("/long").unwrap().map(|entry| entry.unwrap().file_name().into_string().unwrap()).collect();
+    assert_eq!(names, ["a", long.as_str(), "z"]);
+}
+
+#[test]
+fn metadata_and_streams() {
+    mock::fs::add_file("/cfg/settings.ini", "volume=3");
+    mock::fs::add_stream("/dev/kbd", "abc");
+
+    let meta = fs::metadata("/cfg/settings.ini").unwrap();
+    assert_eq!(meta.len(), 8);
+    assert!(meta.is_file());
+    assert!(!meta.permissions().readonly());
+    assert!(fs::metadata("/cfg").unwrap().is_dir());
+    assert_eq!(fs::metadata("/cfg/missing").unwrap_err().kind(), ErrorKind::NotFound);
+
+    let entry = fs::read_dir("/cfg").unwrap().next().unwrap().unwrap();
+    assert_eq!(entry.metadata().unwrap().len(), 8);
+
+    let mut dev = File::open("/dev/kbd").unwrap();
+    let meta = dev.metadata().unwrap();
+    assert!(meta.file_type().is_stream());
+    assert_eq!(meta.len(), 0);
+    // nothing gets eaten by opening the device
+    let mut first = [0u8; 1];
+    dev.read_exact(&mut first).unwrap();
+    assert_eq!(&first, b"a");
+    assert_eq!(dev.seek(SeekFrom::Start(0)).unwrap_err().kind(), ErrorKind::NotSeekable);
+    assert_eq!(dev.stream_position().unwrap_err().kind(), ErrorKind::NotSeekable);
+    let mut rest = String::new();
+    dev.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "bc");
+
+    mock::fs::add_stream("/dev/log", "streamed text");
+    assert_eq!(fs::read_to_string("/dev/log").unwrap(), "streamed text");
+
+    // opening and reading only needs fstat to tell empty files from streams
+    mock::fs::set_fstat_failing(Some(FsResult::DriverError as i32));
+    let mut file = File::open("/cfg/settings.ini").unwrap();
+    assert_eq!(file.seek(SeekFrom::End(-1)).unwrap(), 7);
+    mock::fs::add_file("/cfg/empty", "");
+    assert_eq!(fs::read("/cfg/empty").unwrap(), b"");
+    mock::fs::add_stream("/dev/log", "still streamed");
+    assert_eq!(fs::read_to_string("/dev/log").unwrap(), "still streamed");
+    mock::fs::set_fstat_failing(None);
+
+    mock::fs::set_readonly(true);
+    assert!(fs::metadata("/cfg/settings.ini").unwrap().permissions().readonly());
+}
+
+#[test]
+fn empty_files_without_fstat() {
+    mock::fs::set_proposed_abi(false);
+    mock::fs::add_file("/cfg/empty", "");
+    mock::fs::add_file("/cfg/full", "data");
+
+    let mut file = File::open("/cfg/empty").unwrap();
+    let meta = file.metadata().unwrap();
+    assert!(meta.is_file());
+    assert_eq!(meta.len(), 0);
+    assert_eq!(file.seek(SeekFrom::End(0)).unwrap(), 0);
+    assert_eq!(file.stream_position().unwrap(), 0);
+    let mut buf = Vec::new();
+    assert_eq!(file.read_to_end(&mut buf).unwrap(), 0);
+    // the guess is kept instead of asking again on every call
+    let fstats = mock::fs::fstat_count();
+    file.read_to_end(&mut buf).unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    assert_eq!(mock::fs::fstat_count(), fstats);
+
+    assert_eq!(File::open("/cfg/full").unwrap().metadata().unwrap().len(), 4);
+    mock::fs::add_stream("/dev/log", "streamed");
+    assert_eq!(fs::read_to_string("/dev/log").unwrap(), "streamed");
+    mock::fs::set_proposed_abi(true);
+}
+
+#[test]
+fn directory_operations() {
+    fs::create_dir("/games").unwrap();
+    assert_eq!(fs::create_dir("/games").unwrap_err().kind(), ErrorKind::AlreadyExists);
+    assert_eq!(fs::create_dir("/nope/inner").unwrap_err().kind(), ErrorKind::NotFound);
+    fs::create_dir_all("/games/saves/slot1/").unwrap();
+    fs::create_dir_all("/games/saves").unwrap();
+    assert!(fs::metadata("/games/saves/slot1").unwrap().is_dir());
+
+    fs::write("/games/saves/slot1/state", "42").unwrap();
+    assert_eq!(fs::create_dir_all("/games/saves/slot1/state").unwrap_err().kind(), ErrorKind::AlreadyExists);
+    assert_eq!(fs::create_dir("/games/saves/slot1/state/x").unwrap_err().kind(), ErrorKind::NotADirectory);
+    assert_eq!(fs::copy("/games/saves/slot1/state", "/games/backup").unwrap(), 2);
+    assert_eq!(fs::read_to_string("/games/backup").unwrap(), "42");
+
+    assert_eq!(fs::remove_dir("/games/saves").unwrap_err().kind(), ErrorKind::DirectoryNotEmpty);
+    assert_eq!(fs::remove_dir("/games/backup").unwrap_err().kind(), ErrorKind::NotADirectory);
+    assert_eq!(fs::remove_file("/games/saves").unwrap_err().kind(), ErrorKind::IsADirectory);
+    assert_eq!(fs::remove_file("/games/none").unwrap_err().kind(), ErrorKind::NotFound);
+
+    fs::rename("/games/saves", "/games/old").unwrap();
+    assert!(!fs::exists("/games/saves").unwrap());
+    assert_eq!(fs::read("/games/old/slot1/state").unwrap(), b"42");
+    assert_eq!(fs::rename("/games/backup", "/games/old").unwrap_err().kind(), ErrorKind::IsADirectory);
+    fs::rename("/games/backup", "/games/old/slot1/state").unwrap();
+    assert!(!fs::exists("/games/backup").unwrap());
+
+    assert_eq!(fs::remove_dir_all("/games/old/slot1/state").unwrap_err().kind(), ErrorKind::NotADirectory);
+    fs::remove_dir_all("/games").unwrap();
+    assert!(!fs::exists("/games").unwrap());
+    assert!(fs::exists("/").unwrap());
+
+    mock::fs::set_readonly(true);
+    assert_eq!(fs::create_dir("/ro").unwrap_err().kind(), ErrorKind::ReadOnlyFilesystem);
+}
+
+fn walk_tree() {
+    mock::fs::add_file("/assets/b.png", "");
+    mock::fs::add_file("/assets/a.png", "");
+    mock::fs::add_file("/assets/levels/level1.dat", "");
+    mock::fs::add_file("/assets/levels/level2.dat", "");
+    mock::fs::add_file("/assets/levels/extra/level3.dat", "");
+    mock::fs::add_file("/assets/levels/extra/level10.dat", "");
+    mock::fs::add_file("/assets/sounds/hit.wav", "");
+}
+
+fn walked(iter: impl Iterator<Item = io::Result<fs::WalkEntry>>) -> Vec<(String, usize)> {
+    iter.map(|entry| {
+        let entry = entry.unwrap();
+        (entry.path().as_unix_str().to_str().unwrap().to_string(), entry.depth())
+    }).collect()
+}
+
+#[test]
+fn walk_directories() {
+    walk_tree();
+    let all = walked(fs::walk_dir("/assets").sorted(true).into_iter());
+    assert_eq!(all, [
+        ("/assets".to_string(), 0),
+        ("/assets/a.png".to_string(), 1),
+        ("/assets/b.png".to_string(), 1),
+        ("/assets/levels".to_string(), 1),
+        ("/assets/levels/extra".to_string(), 2),
+        ("/assets/levels/extra/level10.dat".to_string(), 3),
+        ("/assets/levels/extra/level3.dat".to_string(), 3),
+        ("/assets/levels/level1.dat".to_string(), 2),
+        ("/assets/levels/level2.dat".to_string(), 2),
+        ("/assets/sounds".to_string(), 1),
+        ("/assets/sounds/hit.wav".to_string(), 2),
+    ]);
+    assert_eq!(fs::walk_dir("/assets").into_iter().count(), all.len());
+
+    let shallow = walked(fs::walk_dir("/assets").min_depth(1).max_depth(1).sorted(true).into_iter());
+    assert_eq!(shallow.iter().map(|(path, _)| path.as_str()).collect::<Vec<_>>(), ["/assets/a.png", "/assets/b.png", "/assets/levels", "/assets/sounds"]);
+
+    let filtered = walked(fs::walk_dir("/assets").sorted(true).into_iter().filter_entry(|entry| entry.file_name() != "extra"));
+    assert!(filtered.iter().all(|(path, _)| !path.contains("extra")));
+    assert_eq!(filtered.len(), all.len() - 3);
+
+    let mut iter = fs::walk_dir("/assets").sorted(true).into_iter();
+    let mut seen = Vec::new();
+    while let Some(entry) = iter.next() {
+        let entry = entry.unwrap();
+        if entry.file_name() == "levels" {
+            iter.skip_current_dir();
+        }
+        seen.push(entry.into_path());
+    }
+    assert_eq!(seen.len(), all.len() - 5);
+
+    let file = walked(fs::walk_dir("/assets/a.png").into_iter());
+    assert_eq!(file, [("/assets/a.png".to_string(), 0)]);
+    assert_eq!(fs::walk_dir("/missing").into_iter().next().unwrap().unwrap_err().kind(), ErrorKind::NotFound);
+}
+
+#[test]
+fn glob_patterns() {
+    let pattern = fs::Pattern::new("assets/**/level?.dat").unwrap();
+    assert!(pattern.matches("assets/level1.dat"));
+    assert!(pattern.matches("assets/a/b/level2.dat"));
+    assert!(pattern.matches("./assets/x/level3.dat"));
+    assert!(!pattern.matches("assets/level10.dat"));
+    assert!(!pattern.matches("/assets/level1.dat"));
+
+    let pattern = fs::Pattern::new("*.[a-p][!x]g").unwrap();
+    assert!(pattern.matches("a.png"));
+    assert!(pattern.matches(".jpg"));
+    assert!(!pattern.matches("a.pxg"));
+    assert!(!pattern.matches("dir/a.png"));
+    assert!(fs::Pattern::new("[]]").unwrap().matches("]"));
+
+    let long = "a".repeat(100);
+    assert!(!fs::Pattern::new("a*a*a*a*a*a*a*a*a*a*b").unwrap().matches(&long));
+    assert!(fs::Pattern::new("a*a*a*a*a*a*a*a*a*a*").unwrap().matches(&long));
+    let deep = ["a"; 60].join("/");
+    assert!(!fs::Pattern::new("**/a/**/a/**/a/**/a/**/b").unwrap().matches(&deep));
+    assert!(fs::Pattern::new("a/**/a/**/a").unwrap().matches(&deep));
+    assert_eq!(fs::Pattern::new("a/[bc").unwrap_err().pos, 2);
+
+    walk_tree();
+    let found = |pattern: &str| -> Vec<String> {
+        fs::glob(pattern).unwrap().map(|path| path.unwrap().as_unix_str().to_str().unwrap().to_string()).collect()
+    };
+    assert_eq!(found("/assets/*.png"), ["/assets/a.png", "/assets/b.png"]);
+    assert_eq!(found("/assets/**/level?.dat"), ["/assets/levels/extra/level3.dat", "/assets/levels/level1.dat", "/assets/levels/level2.dat"]);
+    assert_eq!(found("/assets/*/*.wav"), ["/assets/sounds/hit.wav"]);
+    assert_eq!(found("/assets/sounds/hit.wav"), ["/assets/sounds/hit.wav"]);
+    assert!(found("/nothing/**").is_empty());
+    assert_eq!(fs::glob("/assets/[").unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[test]
+fn normalize_paths() {
+    let normalized = |path: &str| fs::normalize_lexically(path).as_unix_str().to_str().unwrap().to_string();
+    assert_eq!(normalized("/a/./b/../c/"), "/a/c");
+    assert_eq!(normalized("/../.."), "/");
+    assert_eq!(normalized("a/../../b"), "../b");
+    assert_eq!(normalized("./"), ".");
+    assert_eq!(normalized("a//b"), "a/b");
+}
+
+#[test]
+fn current_dir_resolution() {
+    use redacted_shared_rs::env;
+
+    mock::fs::add_file("/home/user/data.txt", "data");
+    mock::fs::add_file("/home/user/docs/readme.md", "readme");
+    assert_eq!(env::current_dir().unwrap(), Path::new("/"));
+
+    env::set_current_dir("home/user").unwrap();
+    assert_eq!(env::current_dir().unwrap(), Path::new("/home/user"));
+    assert_eq!(fs::read_to_string("data.txt").unwrap(), "data");
+    assert_eq!(fs::read_to_string("../user/./docs/../data.txt").unwrap(), "data");
+    assert_eq!(fs::absolute("docs/x").unwrap(), Path::new("/home/user/docs/x"));
+    assert_eq!(fs::canonicalize("docs/..").unwrap(), Path::new("/home/user"));
+    assert_eq!(fs::canonicalize("nope").unwrap_err().kind(), ErrorKind::NotFound);
+
+    let found: Vec<_> = fs::glob("**/*.md").unwrap().map(|path| path.unwrap()).collect();
+    assert_eq!(found, [Path::new("docs/readme.md")]);
+    let found: Vec<_> = fs::glob("**").unwrap().map(|path| path.unwrap()).collect();
+    assert_eq!(found, [Path::new("data.txt"), Path::new("docs"), Path::new("docs/readme.md")]);
+    let names: Vec<_> = fs::read_dir("docs").unwrap().map(|entry| entry.unwrap().path()).collect();
+    assert_eq!(names, [Path::new("docs/readme.md")]);
+
+    env::set_current_dir("docs").unwrap();
+    assert_eq!(env::current_dir().unwrap(), Path::new("/home/user/docs"));
+    assert_eq!(env::set_current_dir("/nope").unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(env::set_current_dir("readme.md").unwrap_err().kind(), ErrorKind::NotADirectory);
+    assert_eq!(env::current_dir().unwrap(), Path::new("/home/user/docs"));
+
+    env::set_current_dir("/").unwrap();
+}
+
+#[test]
+fn current_dir_without_stat() {
+    use redacted_shared_rs::env;
+
+    mock::fs::set_proposed_abi(false);
+    mock::fs::add_file("/home/user/data.txt", "data");
+    assert_eq!(fs::metadata("/home/user").unwrap_err().kind(), ErrorKind::Unsupported);
+
+    env::set_current_dir("/home/./user/").unwrap();
+    assert_eq!(env::current_dir().unwrap(), Path::new("/home/user"));
+    assert_eq!(fs::canonicalize("data.txt").unwrap(), Path::new("/home/user/data.txt"));
+    assert_eq!(fs::canonicalize("../user").unwrap(), Path::new("/home/user"));
+    assert_eq!(fs::canonicalize("nope").unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(env::set_current_dir("/nope").unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(fs::read_to_string("data.txt").unwrap(), "data");
+    mock::fs::set_proposed_abi(true);
+}
+
+#[test]
+fn vfs_mounts() {
+    use redacted_shared_rs::fs::vfs::{self, EmbeddedFs, MemoryFs, OsFs};
+
+    let mut archive = tar_entry("sprites/", b'5', b"");
+    archive.extend(tar_entry("sprites/ship.bin", b'0', &[1, 2, 3]));
+    archive.extend(tar_entry("empty/", b'5', b""));
+    archive.extend(tar_entry("readme.txt", b'0', b"hello"));
+    archive.extend([0; 1024]);
+    vfs::mount("/vfs_tar", EmbeddedFs::from_tar(archive.leak()).unwrap()).unwrap();
+
+    assert_eq!(fs::read("/vfs_tar/sprites/ship.bin").unwrap(), [1, 2, 3]);
+    assert_eq!(fs::read_to_string("/vfs_tar/readme.txt").unwrap(), "hello");
+    let names: Vec<_> = fs::read_dir("/vfs_tar").unwrap().map(|entry| entry.unwrap().path()).collect();
+    assert_eq!(names, [Path::new("/vfs_tar/empty"), Path::new("/vfs_tar/readme.txt"), Path::new("/vfs_tar/sprites")]);
+    assert!(fs::metadata("/vfs_tar/empty").unwrap().is_dir());
+    assert_eq!(fs::metadata("/vfs_tar/readme.txt").unwrap().len(), 5);
+    assert!(fs::metadata("/vfs_tar/readme.txt").unwrap().permissions().readonly());
+    assert_eq!(File::open("/vfs_tar/missing").unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(File::open("/vfs_tar/sprites").unwrap_err().kind(), ErrorKind::IsADirectory);
+    assert_eq!(File::create("/vfs_tar/new").unwrap_err().kind(), ErrorKind::ReadOnlyFilesystem);
+
+    let mut file = File::open("/vfs_tar/readme.txt").unwrap();
+    file.seek(SeekFrom::Start(1)).unwrap();
+    let mut clone = file.try_clone().unwrap();
+    let mut rest = String::new();
+    clone.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, "ello");
+    assert_eq!(file.write(b"x").unwrap_err().kind(), ErrorKind::ReadOnlyFilesystem);
+
+    // nested mounts win over the ones they're inside of
+    vfs::mount("/vfs_tar/sprites", MemoryFs::new().with_file("a/b.txt", "b").with_dir("c")).unwrap();
+    assert_eq!(fs::read_to_string("/vfs_tar/sprites/a/b.txt").unwrap(), "b");
+    let found: Vec<_> = fs::glob("/vfs_tar/sprites/**").unwrap().map(|path| path.unwrap()).collect();
+    assert_eq!(found, [Path::new("/vfs_tar/sprites"), Path::new("/vfs_tar/sprites/a"), Path::new("/vfs_tar/sprites/a/b.txt"), Path::new("/vfs_tar/sprites/c")]);
+    assert_eq!(vfs::mount("/vfs_tar/sprites", MemoryFs::new()).unwrap_err().kind(), ErrorKind::AlreadyExists);
+    vfs::unmount("/vfs_tar/sprites").unwrap();
+    assert_eq!(fs::read("/vfs_tar/sprites/ship.bin").unwrap(), [1, 2, 3]);
+    vfs::unmount("/vfs_tar").unwrap();
+    assert_eq!(vfs::unmount("/vfs_tar").unwrap_err().kind(), ErrorKind::NotFound);
+    assert_eq!(fs::read("/vfs_tar/readme.txt").unwrap_err().kind(), ErrorKind::NotFound);
+
+    mock::fs::add_file("/real/dir/data.txt", "real");
+    vfs::mount("/vfs_os", OsFs::new("/real")).unwrap();
+    assert_eq!(fs::read_to_string("/vfs_os/dir/data.txt").unwrap(), "real");
+    fs::write("/vfs_os/dir/new.txt", "written").unwrap();
+    assert_eq!(mock::fs::contents("/real/dir/new.txt").unwrap(), b"written");
+    let names: Vec<_> = fs::read_dir("/vfs_os/dir").unwrap().map(|entry| entry.unwrap().file_name()).collect();
+    assert_eq!(names.len(), 2);
+    assert!(fs::metadata("/vfs_os/dir").unwrap().is_dir());
+    vfs::unmount("/vfs_os").unwrap();
+
+    // removing and creating trees only looks at what's really there, not at mounts
+    mock::fs::add_file("/under/kept.txt", "os");
+    mock::fs::add_file("/under/sub/deep.txt", "os");
+    vfs::mount("/under", MemoryFs::new().with_file("kept.txt", "mounted").with_file("other.txt", "mounted")).unwrap();
+    fs::remove_dir_all("/under").unwrap();
+    assert!(mock::fs::contents("/under/kept.txt").is_none());
+    assert!(mock::fs::contents("/under/sub/deep.txt").is_none());
+    assert_eq!(fs::read_to_string("/under/kept.txt").unwrap(), "mounted");
+    assert_eq!(fs::remove_dir_all("/under").unwrap_err().kind(), ErrorKind::NotFound);
+    fs::create_dir_all("/under/made").unwrap();
+    assert!(mock::fs::contents("/under/made").is_some());
+    vfs::unmount("/under").unwrap();
+
+    let bad = tar_entry("x", b'0', b"x");
+    let mut corrupt = bad.clone();
+    corrupt[0] = b'y';
+    assert_eq!(EmbeddedFs::from_tar(corrupt.leak()).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(EmbeddedFs::from_tar(bad[..512].to_vec().leak()).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
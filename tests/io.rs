@@ -0,0 +1,297 @@
+use redacted_shared_rs::{io::{self, BufRead, BufReader, BufWriter, Cursor, ErrorKind, LineWriter, Read, Seek, SeekFrom, Write, input::{keyboard::{self, KeyEventType, RawKeyEvent, RawKeypress}, keycodes::{Key, ModifierKey}}}, mock};
+
+#[derive(Debug, Default)]
+struct Recorder {
+    writes: Vec<Vec<u8>>,
+}
+
+impl Write for Recorder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writes.push(buf.to_vec());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn println_reaches_printl() {
+    redacted_shared_rs::println!("hello {}", 42);
+    redacted_shared_rs::println!();
+    assert_eq!(mock::console::take_lines(), ["hello 42", ""]);
+}
+
+#[test]
+fn puts_reaches_printl() {
+    io::puts("abc");
+    assert_eq!(mock::console::take_output(), "abc\n");
+}
+
+#[test]
+fn console_writer_splits_and_escapes() {
+    let mut out = io::ConsoleWriter::<8>::new();
+    out.write_bytes(b"0123456789\nnul\0byte\n");
+    out.write_bytes(b"ab");
+    out.finish();
+    assert_eq!(mock::console::take_lines(), ["0123456", "789", "nul\\0by", "te", "ab"]);
+
+    let mut out = io::ConsoleWriter::<8>::new();
+    out.write_bytes("abcde\u{e9}\u{1f600}".as_bytes());
+    drop(out);
+    assert_eq!(mock::console::take_lines(), ["abcde\u{e9}", "\u{1f600}"]);
+
+    io::putfmt(format_args!("a\0{}", 1)).unwrap();
+    redacted_shared_rs::println!("b\0");
+    assert_eq!(mock::console::take_lines(), ["a\\01", "b\\0"]);
+}
+
+#[test]
+fn stdout_buffers_until_newline() {
+    let mut out = io::stdout().lock();
+    write!(out, "a").unwrap();
+    out.write_all(b"b").unwrap();
+    assert!(mock::console::take_lines().is_empty());
+
+    writeln!(out, "c\nd").unwrap();
+    write!(out, "e").unwrap();
+    assert_eq!(mock::console::take_lines(), ["abc", "d"]);
+
+    out.flush().unwrap();
+    assert_eq!(mock::console::take_lines(), ["e"]);
+}
+
+struct Nested;
+
+impl core::fmt::Display for Nested {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        redacted_shared_rs::print!("inner {} {}", 1, 2);
+        redacted_shared_rs::println!(" done");
+        f.write_str("outer")
+    }
+}
+
+#[test]
+fn print_inside_print_keeps_lines_whole() {
+    redacted_shared_rs::print!("{}", Nested);
+    redacted_shared_rs::println!();
+    assert_eq!(mock::console::take_lines(), ["inner 1 2", " done", "outer"]);
+}
+
+#[test]
+fn buf_writer_batches_writes() {
+    let mut writer = BufWriter::with_capacity(8, Recorder::default());
+    writer.write_all(b"abc").unwrap();
+    writer.write_all(b"def").unwrap();
+    assert!(writer.get_ref().writes.is_empty());
+    writer.write_all(b"ghi").unwrap();
+    writer.write_all(b"0123456789").unwrap();
+    let recorder = writer.into_inner().unwrap();
+    assert_eq!(recorder.writes, [b"abcdef".to_vec(), b"ghi".to_vec(), b"0123456789".to_vec()]);
+}
+
+#[test]
+fn line_writer_flushes_lines() {
+    let mut writer = LineWriter::new(Recorder::default());
+    write!(writer, "one").unwrap();
+    assert!(writer.get_ref().writes.is_empty());
+    write!(writer, "\ntwo\nthr").unwrap();
+    assert_eq!(writer.get_ref().writes, [b"one".to_vec(), b"\ntwo\n".to_vec()]);
+    writer.flush().unwrap();
+    assert_eq!(writer.get_ref().writes.last().unwrap(), b"thr");
+}
+
+#[test]
+fn buf_reader_seeks_past_its_buffer() {
+    let mut reader = BufReader::with_capacity(4, Cursor::new(b"0123456789".to_vec()));
+    assert_eq!(reader.fill_buf().unwrap(), b"0123");
+    reader.consume(1);
+    assert_eq!(reader.seek(SeekFrom::Current(2)).unwrap(), 3);
+    assert_eq!(reader.fill_buf().unwrap(), b"3456");
+    assert_eq!(reader.seek(SeekFrom::Current(i64::MIN)).unwrap_err().kind(), ErrorKind::InvalidInput);
+    assert_eq!(reader.stream_position().unwrap(), 3);
+}
+
+#[test]
+fn eprint_and_dbg() {
+    redacted_shared_rs::eprint!("x = ");
+    assert_eq!(redacted_shared_rs::dbg!(1 + 1), 2);
+    let lines = mock::console::take_lines();
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with("x = [tests/io.rs:"));
+    assert!(lines[0].ends_with("] 1 + 1 = 2"));
+}
+
+#[test]
+fn read_event_follows_queue() {
+    mock::keyboard::press(Key::KEY_H);
+    let event = keyboard::read_event().unwrap();
+    assert!(matches!(event.ty, keyboard::KeyEventType::KeyPress));
+    assert_eq!(event.key.as_char(), 'h');
+    assert!(matches!(keyboard::read_event().unwrap().ty, keyboard::KeyEventType::KeyRelease));
+    assert!(keyboard::read_event().is_none());
+}
+
+#[test]
+fn hid_codes_roundtrip() {
+    for hid in 0..=u8::MAX {
+        assert_eq!(Key::from_hid(hid).hid(), hid);
+        assert_eq!(u8::from(Key::from_hid(hid)), hid);
+        if let Ok(key) = Key::try_from(hid) {
+            assert_eq!(key.hid(), hid);
+        }
+    }
+    assert_eq!(Key::try_from(0x0b), Ok(Key::KEY_H));
+    assert_eq!(Key::try_from(0xe0), Ok(Key::KEY_LEFTCTRL));
+    assert_eq!(Key::try_from(0x02), Err(0x02));
+    assert_eq!(Key::from_hid(0x02), Key::Unknown(0x02));
+    assert_eq!(Key::from_hid(0xff), Key::Unknown(0xff));
+}
+
+#[test]
+fn unknown_raw_values_decode() {
+    mock::keyboard::push_raw_event(RawKeyEvent { ty: 9, key: 0x02, modifier: 0 });
+    let event = keyboard::read_event().unwrap();
+    assert_eq!(event.ty, KeyEventType::Unknown(9));
+    assert_eq!(event.key, Key::Unknown(0x02));
+
+    mock::keyboard::push_raw_keypress(RawKeypress { modifier: 0x02, rsvd: 0, keys: [0x0b, 0xfe, 0, 0, 0, 0] });
+    let keypress = keyboard::read_key().unwrap();
+    assert_eq!(keypress.modifier, ModifierKey::LSHIFT);
+    assert_eq!(keypress.keys[0], Key::KEY_H);
+    assert_eq!(keypress.keys[1], Key::Unknown(0xfe));
+}
+
+#[test]
+fn cursor_read_seek_write() {
+    let mut cursor = Cursor::new(Vec::new());
+    cursor.write_all(b"hello").unwrap();
+    cursor.seek(SeekFrom::Start(8)).unwrap();
+    cursor.write_all(b"!").unwrap();
+    assert_eq!(cursor.get_ref(), b"hello\0\0\0!");
+
+    cursor.seek(SeekFrom::End(-9)).unwrap();
+    let mut buf = [0u8; 4];
+    cursor.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hell");
+    assert_eq!(cursor.seek(SeekFrom::Current(-10)).unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    // a short read_exact leaves the position alone
+    let mut buf = [0u8; 8];
+    assert_eq!(cursor.read_exact(&mut buf).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(cursor.position(), 4);
+    assert_eq!(buf, [0; 8]);
+
+    let mut storage = [0u8; 4];
+    let mut cursor = Cursor::new(&mut storage[..]);
+    assert_eq!(cursor.write_all(b"12345").unwrap_err().kind(), ErrorKind::WriteZero);
+    assert_eq!(storage, *b"1234");
+}
+
+#[test]
+fn slice_and_vec() {
+    let mut data: &[u8] = b"abc\ndef";
+    let mut line = String::new();
+    data.read_line(&mut line).unwrap();
+    assert_eq!(line, "abc\n");
+    assert_eq!(data, b"def");
+
+    let mut out = Vec::new();
+    writeln!(out, "{}-{}", 1, 2).unwrap();
+    assert_eq!(out, b"1-2\n");
+}
+
+#[test]
+fn adapters() {
+    let mut reader = (&b"hello"[..]).chain(&b" world"[..]).take(8);
+    let mut s = String::new();
+    reader.by_ref().read_to_string(&mut s).unwrap();
+    assert_eq!(s, "hello wo");
+    assert_eq!(reader.limit(), 0);
+
+    let bytes: Vec<u8> = io::repeat(7).take(3).bytes().map(Result::unwrap).collect();
+    assert_eq!(bytes, [7, 7, 7]);
+
+    let mut out = Vec::new();
+    assert_eq!(io::copy(&mut Cursor::new("copied"), &mut out).unwrap(), 6);
+    assert_eq!(out, b"copied");
+    assert_eq!(io::copy(&mut io::empty(), &mut io::sink()).unwrap(), 0);
+}
+
+#[derive(Debug)]
+struct Custom(u32);
+
+impl core::fmt::Display for Custom {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "custom {}", self.0)
+    }
+}
+
+impl core::error::Error for Custom {}
+
+#[test]
+fn error_inspection() {
+    let mut err = io::Error::new(ErrorKind::InvalidData, "bad");
+    assert!(err.get_ref().is_none());
+    assert!(err.get_mut().is_none());
+    let err = err.downcast::<Custom>().unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidData);
+    assert!(err.into_inner().is_none());
+
+    let mut err = io::Error::from_error(ErrorKind::Other, Custom(1));
+    assert_eq!(err.get_ref().unwrap().downcast_ref::<Custom>().unwrap().0, 1);
+    err.get_mut().unwrap().downcast_mut::<Custom>().unwrap().0 = 2;
+    assert_eq!(err.to_string(), "custom 2");
+    assert_eq!(err.downcast::<Custom>().unwrap().0, 2);
+}
+
+#[test]
+fn error_context() {
+    use core::error::Error as _;
+    use redacted_shared_rs::io::{Context, ContextError};
+
+    let res: io::Result<()> = Err(ErrorKind::NotFound.into());
+    let err = res.with_context(|| format!("opening {}", "/dev/x")).context("loading config").unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert_eq!(err.to_string(), "loading config: opening /dev/x: not found");
+
+    let inner = err.source().unwrap();
+    assert_eq!(inner.to_string(), "opening /dev/x: not found");
+    let root = inner.downcast_ref::<io::Error>().unwrap().source().unwrap();
+    assert_eq!(root.downcast_ref::<io::Error>().unwrap().kind(), ErrorKind::NotFound);
+    assert_eq!(err.downcast::<ContextError>().unwrap().context(), "loading config");
+}
+
+#[test]
+fn borrowed_buf_and_io_slices() {
+    use redacted_shared_rs::io::{BorrowedBuf, IoSliceMut};
+
+    let mut storage = [core::mem::MaybeUninit::<u8>::uninit(); 8];
+    let mut buf = BorrowedBuf::from(&mut storage[..]);
+    let mut src: &[u8] = b"0123456789";
+    src.read_buf(buf.unfilled()).unwrap();
+    assert_eq!(buf.filled(), b"01234567");
+    assert_eq!(src, b"89");
+
+    let mut src: &[u8] = b"abc";
+    let mut storage = [core::mem::MaybeUninit::<u8>::uninit(); 4];
+    let mut buf = BorrowedBuf::from(&mut storage[..]);
+    assert_eq!(src.read_buf_exact(buf.unfilled()).unwrap_err().kind(), ErrorKind::UnexpectedEof);
+    assert_eq!(buf.filled(), b"abc");
+
+    // goes through the default `read_buf`, which zeroes before calling `read`
+    let mut storage = [core::mem::MaybeUninit::<u8>::uninit(); 4];
+    let mut buf = BorrowedBuf::from(&mut storage[..]);
+    io::repeat(7).take(2).read_buf(buf.unfilled()).unwrap();
+    assert_eq!(buf.filled(), [7, 7]);
+    assert_eq!(buf.init_len(), 4);
+
+    let (mut a, mut b) = ([0u8; 2], [0u8; 4]);
+    let mut bufs = [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)];
+    assert_eq!((&b"xyzw"[..]).read_vectored(&mut bufs).unwrap(), 4);
+    let mut bufs = &mut bufs[..];
+    IoSliceMut::advance_slices(&mut bufs, 3);
+    assert_eq!(bufs.len(), 1);
+    assert_eq!(&*bufs[0], [b'w', 0, 0]);
+}
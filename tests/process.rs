@@ -0,0 +1,172 @@
+use std::sync::{Arc, Mutex};
+
+use redacted_shared_rs::{io, mock, print, process::{self, ExitCode, Termination}};
+
+#[test]
+fn exit_process_halts() {
+    assert_eq!(mock::process::catch_exit(|| ExitCode::FAILURE.exit_process()), Err(1));
+}
+
+#[test]
+fn result_reports_error() {
+    let res: Result<(), io::Error> = Err(io::ErrorKind::NotFound.into());
+    assert!(matches!(res.report(), ExitCode::FAILURE));
+    assert!(mock::console::take_output().starts_with("Error: "));
+}
+
+#[test]
+fn errors_pick_the_exit_code() {
+    use process::ExitError;
+
+    fn fails_with_3() -> Result<(), ExitError<io::Error>> {
+        Err(ExitError::new(ExitCode::from(3), io::ErrorKind::NotFound.into()))
+    }
+    fn fails_plainly() -> Result<(), ExitError<io::Error>> {
+        Err(io::Error::from(io::ErrorKind::NotFound))?;
+        Ok(())
+    }
+    fn succeeds() -> Result<ExitCode, ExitError<&'static str>> {
+        Ok(ExitCode::from(4))
+    }
+    assert_eq!(mock::process::run_main(fails_with_3), 3);
+    assert!(mock::console::take_output().starts_with("Error: "));
+    assert_eq!(mock::process::run_main(fails_plainly), 1);
+    assert_eq!(mock::process::run_main(succeeds), 4);
+
+    let error = ExitError::new(ExitCode::from(9), "bad input");
+    assert_eq!(error.code(), ExitCode::from(9));
+    assert_eq!(*error.error(), "bad input");
+    assert_eq!(error.into_inner(), "bad input");
+}
+
+#[test]
+fn exit_codes() {
+    assert_eq!(ExitCode::from(0), ExitCode::SUCCESS);
+    assert_eq!(ExitCode::from(42).to_i32(), 42);
+    assert_eq!(mock::process::catch_exit(|| ExitCode::from(3).exit_process()), Err(3));
+    assert_eq!(mock::process::catch_exit(|| process::exit(7)), Err(7));
+    assert_eq!(mock::process::catch_exit(|| process::exit(-1)), Err(u32::MAX));
+    assert_eq!(mock::process::catch_exit(|| process::abort()), Err(process::ABORT_EXIT_CODE as u32));
+
+    let res: Result<ExitCode, io::Error> = Ok(ExitCode::from(9));
+    assert_eq!(res.report(), ExitCode::from(9));
+    assert_eq!(().report(), ExitCode::SUCCESS);
+
+    // a `fn main() -> !` never gets as far as reporting
+    fn never_returns() -> ! {
+        process::exit(4)
+    }
+    fn report<T: Termination>(main: fn() -> T) -> ExitCode {
+        main().report()
+    }
+    assert_eq!(mock::process::catch_exit(|| report(never_returns)), Err(4));
+}
+
+#[test]
+fn exit_hooks_run_in_reverse() {
+    let order = Arc::new(Mutex::new(Vec::new()));
+    for i in 0..3 {
+        let order = order.clone();
+        process::at_exit(move || order.lock().unwrap().push(i)).unwrap();
+    }
+    let nested = order.clone();
+    process::at_exit(move || {
+        let order = nested.clone();
+        process::at_exit(move || order.lock().unwrap().push(10)).unwrap();
+    }).unwrap();
+
+    print!("no newline yet");
+    assert_eq!(mock::process::catch_exit(|| process::exit(5)), Err(5));
+    assert_eq!(*order.lock().unwrap(), [10, 2, 1, 0]);
+    assert_eq!(mock::console::take_output(), "no newline yet\n");
+
+    // hooks only run once
+    assert_eq!(mock::process::catch_exit(|| process::exit(0)), Err(0));
+    assert_eq!(order.lock().unwrap().len(), 4);
+
+    let aborted = Arc::new(Mutex::new(false));
+    let flag = aborted.clone();
+    process::at_exit(move || *flag.lock().unwrap() = true).unwrap();
+    assert_eq!(mock::process::catch_exit(|| process::abort()), Err(process::ABORT_EXIT_CODE as u32));
+    assert!(!*aborted.lock().unwrap());
+    assert_eq!(mock::process::catch_exit(|| ExitCode::SUCCESS.exit_process()), Err(0));
+    assert!(*aborted.lock().unwrap());
+}
+
+#[test]
+fn spawn_and_wait() {
+    use redacted_shared_rs::{env, process::Command};
+
+    mock::process::add_program("/bin/tool", 2, |spawn| spawn.args.len() as i32 - 1);
+    mock::process::add_program("/bin/fail", 0, |_| 3);
+    mock::fs::add_file("/bin/tool", "");
+
+    let mut child = Command::new("/bin/tool").arg("a").args(["b", "c"]).current_dir("/home").env("SPAWN_TEST_MODE", "fast").spawn().unwrap();
+    assert!(child.id() > 0);
+    assert_eq!(child.try_wait().unwrap(), None);
+    assert_eq!(child.try_wait().unwrap(), None);
+    let status = child.try_wait().unwrap().unwrap();
+    assert_eq!(status.code(), Some(3));
+    assert!(!status.success());
+    assert_eq!(status.to_string(), "exit status: 3");
+    // the status is kept once the child is gone
+    assert_eq!(child.wait().unwrap(), status);
+    assert_eq!(mock::process::running_count(), 0);
+
+    let spawn = mock::process::spawned().pop().unwrap();
+    assert_eq!(spawn.path, "/bin/tool");
+    assert_eq!(spawn.args, ["/bin/tool", "a", "b", "c"]);
+    assert_eq!(spawn.cwd, "/home");
+    assert!(spawn.env.contains(&"SPAWN_TEST_MODE=fast".to_string()));
+    assert!(spawn.env.contains(&"PWD=/home".to_string()));
+
+    // inherited variables, removals and `PATH` lookups
+    env::set_var("SPAWN_TEST_INHERITED", "yes");
+    env::set_var("SPAWN_TEST_DROPPED", "yes");
+    env::set_var("PATH", "/usr/bin:/bin");
+    let status = Command::new("tool").env_remove("SPAWN_TEST_DROPPED").status().unwrap();
+    assert!(status.success());
+    let spawn = mock::process::spawned().pop().unwrap();
+    assert_eq!(spawn.path, "/bin/tool");
+    assert_eq!(spawn.args, ["tool"]);
+    assert_eq!(spawn.cwd, "/");
+    assert!(spawn.env.contains(&"SPAWN_TEST_INHERITED=yes".to_string()));
+    assert!(!spawn.env.iter().any(|var| var.starts_with("SPAWN_TEST_DROPPED=")));
+    env::remove_var("PATH");
+
+    Command::new("/bin/fail").env_clear().env("ONLY", "this").status().unwrap();
+    assert_eq!(mock::process::spawned().pop().unwrap().env, ["ONLY=this", "PWD=/"]);
+    assert_eq!(Command::new("/bin/fail").status().unwrap().code(), Some(3));
+
+    assert_eq!(Command::new("/bin/missing").spawn().unwrap_err().kind(), io::ErrorKind::NotFound);
+    assert_eq!(Command::new("/bin/tool").arg("nul\0").spawn().unwrap_err().kind(), io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn child_sees_its_env_and_dir() {
+    use redacted_shared_rs::{env, path::Path, process::Command};
+
+    // the child runs through the crate's own start-up, so this is what a Rust program would see
+    mock::process::add_program("/bin/check", 0, |_| {
+        let args: Vec<_> = env::args().collect();
+        let seen = (env::var("CHECK_MODE"), env::var("CHECK_INHERITED"), env::current_dir().unwrap());
+        match seen {
+            (Ok(mode), Ok(inherited), dir) if args == ["/bin/check", "x"] && mode == "on" && inherited == "yes" && dir == Path::new("/srv/data") => 0,
+            _ => 1,
+        }
+    });
+    mock::process::add_program("/bin/bare", 0, |_| {
+        let vars: Vec<_> = env::vars().collect();
+        if vars == [("PWD".to_string(), "/".to_string())] { process::exit(7) } else { 0 }
+    });
+
+    env::set_var("CHECK_INHERITED", "yes");
+    let status = Command::new("/bin/check").arg("x").env("CHECK_MODE", "on").current_dir("/srv/./data").status().unwrap();
+    assert!(status.success());
+    // and the parent keeps its own
+    assert_eq!(env::current_dir().unwrap(), Path::new("/"));
+    assert!(env::var("CHECK_MODE").is_err());
+
+    // a cleared environment still says where the child is, `exit` in the child is its status
+    assert_eq!(Command::new("/bin/bare").env_clear().status().unwrap().code(), Some(7));
+}
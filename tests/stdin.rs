@@ -0,0 +1,111 @@
+use redacted_shared_rs::{io::{self, input::keycodes::{Key, ModifierKey}, BufRead, Read}, mock};
+
+fn read_line() -> String {
+    let mut line = String::new();
+    io::stdin().read_line(&mut line).unwrap();
+    line
+}
+
+#[test]
+fn reads_and_echoes_line() {
+    mock::keyboard::type_str("Hi!\n");
+    assert_eq!(read_line(), "Hi!\n");
+    assert_eq!(mock::console::take_lines(), ["H", "Hi", "Hi!"]);
+
+    // edits show up as they happen, the cursor as a `|` when it isn't at the end
+    mock::keyboard::type_str("ab");
+    mock::keyboard::press(Key::KEY_LEFT);
+    mock::keyboard::press(Key::KEY_BACKSPACE);
+    mock::keyboard::press(Key::KEY_BACKSPACE);
+    mock::keyboard::press(Key::KEY_END);
+    mock::keyboard::type_str("\n");
+    assert_eq!(read_line(), "b\n");
+    assert_eq!(mock::console::take_lines(), ["a", "ab", "a|b", "|b", "b"]);
+
+    // an empty line still gets echoed
+    mock::keyboard::type_str("\n");
+    assert_eq!(read_line(), "\n");
+    assert_eq!(mock::console::take_lines(), [""]);
+}
+
+#[test]
+fn reading_while_locked_is_busy() {
+    let lock = io::stdin().lock();
+    let mut line = String::new();
+    assert_eq!(io::stdin().read_line(&mut line).unwrap_err().kind(), io::ErrorKind::ResourceBusy);
+    assert!(std::panic::catch_unwind(|| io::stdin().lock()).is_err());
+    drop(lock);
+    mock::keyboard::type_str("ok\n");
+    assert_eq!(read_line(), "ok\n");
+}
+
+#[test]
+fn cursor_editing() {
+    mock::keyboard::type_str("helo");
+    mock::keyboard::press(Key::KEY_LEFT);
+    mock::keyboard::type_str("l");
+    mock::keyboard::press(Key::KEY_END);
+    mock::keyboard::type_str("x");
+    mock::keyboard::press(Key::KEY_BACKSPACE);
+    mock::keyboard::press(Key::KEY_HOME);
+    mock::keyboard::type_str(">");
+    mock::keyboard::press(Key::KEY_RIGHT);
+    mock::keyboard::press(Key::KEY_DELETE);
+    mock::keyboard::type_str("\n");
+    assert_eq!(read_line(), ">hllo\n");
+}
+
+#[test]
+fn history() {
+    let stdin = io::stdin();
+    stdin.set_echo(false);
+    stdin.set_history(true);
+
+    mock::keyboard::type_str("one\ntwo\n");
+    assert_eq!(read_line(), "one\n");
+    assert_eq!(read_line(), "two\n");
+
+    mock::keyboard::type_str("dr");
+    mock::keyboard::press(Key::KEY_UP);
+    mock::keyboard::press(Key::KEY_UP);
+    mock::keyboard::press(Key::KEY_UP);
+    mock::keyboard::type_str("!\n");
+    assert_eq!(read_line(), "one!\n");
+
+    mock::keyboard::type_str("af");
+    mock::keyboard::press(Key::KEY_UP);
+    mock::keyboard::press(Key::KEY_DOWN);
+    mock::keyboard::press(Key::KEY_DOWN);
+    mock::keyboard::type_str("t\n");
+    assert_eq!(read_line(), "aft\n");
+
+    stdin.set_history(false);
+    stdin.set_echo(true);
+    assert!(mock::console::take_lines().is_empty());
+}
+
+#[test]
+fn read_splits_lines_and_ctrl_d_ends_input() {
+    io::stdin().set_echo(false);
+    mock::keyboard::type_str("abc\n");
+    mock::keyboard::press_with(Key::KEY_D, ModifierKey::LCTRL);
+
+    let mut stdin = io::stdin();
+    let mut buf = [0u8; 2];
+    assert_eq!(stdin.read(&mut buf).unwrap(), 2);
+    assert_eq!(&buf, b"ab");
+    let mut rest = Vec::new();
+    stdin.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"c\n");
+    io::stdin().set_echo(true);
+}
+
+#[test]
+fn lines_until_ctrl_d() {
+    io::stdin().set_echo(false);
+    mock::keyboard::type_str("a\nb\n");
+    mock::keyboard::press_with(Key::KEY_D, ModifierKey::LCTRL);
+    let lines: Vec<String> = io::stdin().lock().lines().map(Result::unwrap).collect();
+    assert_eq!(lines, ["a", "b"]);
+    io::stdin().set_echo(true);
+}
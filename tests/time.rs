@@ -0,0 +1,16 @@
+use redacted_shared_rs::{mock, thread, time::{Duration, SystemTime}};
+
+#[test]
+fn clock_is_controllable() {
+    mock::time::set_millis(1000);
+    let start = SystemTime::now();
+    mock::time::advance(Duration::from_millis(250));
+    assert_eq!(start.elapsed(), Duration::from_millis(250));
+}
+
+#[test]
+fn sleep_advances_clock() {
+    let start = SystemTime::now();
+    thread::sleep(Duration::from_secs(2));
+    assert_eq!(SystemTime::now().duration_since(start), Duration::from_secs(2));
+}